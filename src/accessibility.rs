@@ -0,0 +1,78 @@
+use raylib::prelude::*;
+
+use crate::material::Material;
+use crate::render::Renderer;
+
+// the okabe-ito palette, told apart under the common kinds of color blindness
+const SKY_BLUE: Color = Color::new(86, 180, 233, 255);
+const VERMILLION: Color = Color::new(213, 94, 0, 255);
+const ORANGE: Color = Color::new(230, 159, 0, 255);
+const BLUE: Color = Color::new(0, 114, 178, 255);
+const YELLOW: Color = Color::new(240, 228, 66, 255);
+const PURPLE: Color = Color::new(204, 121, 167, 255);
+const INK: Color = Color::new(0, 0, 0, 200);
+// patterns are left off balls drawn smaller than this, in pixels
+const MIN_PATTERN_RADIUS: f32 = 4.0;
+
+// each material gets its own pattern so it reads without the color
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Pattern {
+    Plain,
+    Ring,
+    Stripes,
+    Cross,
+    Dots,
+}
+
+impl Pattern {
+    pub fn of(material: &Material) -> Pattern {
+        match material.name {
+            "rubber" => Pattern::Ring,
+            "wood" => Pattern::Stripes,
+            "steel" => Pattern::Cross,
+            "sand" => Pattern::Dots,
+            _ => Pattern::Plain,
+        }
+    }
+
+    // `center` and `radius` on screen
+    pub fn draw(&self, center: Vector2, radius: f32, d: &mut impl Renderer) {
+        if radius < MIN_PATTERN_RADIUS {
+            return;
+        }
+
+        match self {
+            Pattern::Plain => {}
+            Pattern::Ring => d.ring(center, radius * 0.45, radius * 0.6, INK),
+            Pattern::Stripes => {
+                for y in [-0.5f32, 0.0, 0.5] {
+                    let half = radius * (1.0 - y * y).sqrt() * 0.9;
+                    let offset = Vector2::new(0.0, y * radius);
+                    d.line(center + offset - Vector2::new(half, 0.0), center + offset + Vector2::new(half, 0.0), 2.0, INK);
+                }
+            }
+            Pattern::Cross => {
+                let arm = radius * 0.6;
+                d.line(center - Vector2::new(arm, arm), center + Vector2::new(arm, arm), 2.0, INK);
+                d.line(center - Vector2::new(arm, -arm), center + Vector2::new(arm, -arm), 2.0, INK);
+            }
+            Pattern::Dots => {
+                for (x, y) in [(-0.4, -0.4), (0.4, -0.4), (-0.4, 0.4), (0.4, 0.4)] {
+                    d.circle(center + Vector2::new(x, y) * radius, radius * 0.14, INK);
+                }
+            }
+        }
+    }
+}
+
+// the fill a ball gets in accessible mode, by material rather than its own color
+pub fn color(material: &Material) -> Color {
+    match material.name {
+        "elastic" => SKY_BLUE,
+        "rubber" => VERMILLION,
+        "wood" => ORANGE,
+        "steel" => BLUE,
+        "sand" => YELLOW,
+        _ => PURPLE,
+    }
+}
@@ -0,0 +1,109 @@
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use raylib::prelude::*;
+
+use balls::snapshot;
+use balls::world::World;
+
+use crate::locale::Locale;
+
+const INTERVAL: f32 = 30.0;
+
+pub enum Restore {
+    Pending,
+    Accept(Box<World>),
+    Discard,
+    // dismissed; the snapshot was moved to this path so autosaving can go on
+    Kept(String),
+}
+
+// snapshots the world every INTERVAL seconds and removes the snapshot on a clean exit, so one found at
+// launch was left by a crash and is offered back before anything overwrites it
+pub struct Autosave {
+    path: &'static str,
+    timer: f32,
+    // how old the snapshot found at launch is, if the file system knows
+    found: Option<Option<Duration>>,
+}
+
+impl Autosave {
+    pub fn new(path: &'static str) -> Self {
+        let found = fs::metadata(path).ok().map(|meta| meta.modified().ok().and_then(|t| t.elapsed().ok()));
+
+        Self { path, timer: INTERVAL, found }
+    }
+
+    pub fn is_prompting(&self) -> bool {
+        self.found.is_some()
+    }
+
+    pub fn update(&mut self, world: &World, dt: f32) -> io::Result<()> {
+        if self.is_prompting() {
+            return Ok(());
+        }
+
+        self.timer -= dt;
+        if self.timer > 0.0 {
+            return Ok(());
+        }
+
+        self.timer = INTERVAL;
+        self.save(world)
+    }
+
+    pub fn save(&self, world: &World) -> io::Result<()> {
+        if self.is_prompting() {
+            return Ok(());
+        }
+        snapshot::save(world, self.path)
+    }
+
+    // nothing to recover after a clean exit; an unanswered snapshot is still left for the next launch
+    pub fn clear(&self) -> io::Result<()> {
+        if self.is_prompting() || !Path::new(self.path).exists() {
+            return Ok(());
+        }
+        fs::remove_file(self.path)
+    }
+
+    pub fn draw_prompt(&mut self, locale: &Locale, d: &mut RaylibDrawHandle) -> io::Result<Restore> {
+        let Some(age) = self.found else {
+            return Ok(Restore::Pending);
+        };
+
+        let age = match age {
+            Some(age) if age.as_secs() >= 3600 => locale.format("autosave.hours_ago", &[&(age.as_secs() / 3600)]),
+            Some(age) => locale.format("autosave.minutes_ago", &[&(age.as_secs() / 60)]),
+            None => locale.text("autosave.some_time_ago").to_string(),
+        };
+        let title = CString::new(locale.text("autosave.title")).unwrap();
+        let message = CString::new(locale.format("autosave.prompt", &[&age])).unwrap();
+        let buttons = CString::new(locale.text("autosave.buttons")).unwrap();
+        let choice = d.gui_message_box(Rectangle::new(170.0, 180.0, 300.0, 110.0), Some(&title), Some(&message), Some(&buttons));
+
+        // an unreadable snapshot keeps the prompt up, so it can still be discarded but is never autosaved over
+        match choice {
+            0 => {
+                let aside = Path::new(self.path).with_extension("old.json").to_string_lossy().into_owned();
+                fs::rename(self.path, &aside)?;
+                self.found = None;
+                Ok(Restore::Kept(aside))
+            }
+            1 => {
+                let world = snapshot::load(self.path)?;
+                self.found = None;
+                Ok(Restore::Accept(Box::new(world)))
+            }
+            2 => {
+                self.found = None;
+                fs::remove_file(self.path)?;
+                Ok(Restore::Discard)
+            }
+            _ => Ok(Restore::Pending),
+        }
+    }
+}
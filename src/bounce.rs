@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum BounceRule {
+    // the ball stops bouncing and stays around
+    Inelastic,
+    Despawn,
+}
+
+// what happens to a ball on its `count`th wall impact
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct BounceLimit {
+    pub count: u32,
+    pub rule: BounceRule,
+}
+
+impl BounceLimit {
+    pub fn new(count: u32, rule: BounceRule) -> Self {
+        Self { count, rule }
+    }
+}
@@ -0,0 +1,91 @@
+use raylib::prelude::*;
+
+use crate::Ball;
+
+// uniform grid over the balls' bounds, sized from the average radius; big balls just cover more cells
+#[derive(Default, Clone)]
+pub struct Grid {
+    cells: Vec<Vec<usize>>,
+    pairs: Vec<(usize, usize)>,
+}
+
+impl Grid {
+    // index pairs into `balls` whose bounding boxes overlap, each reported once
+    pub fn pairs(&mut self, balls: &[Ball]) -> &[(usize, usize)] {
+        self.pairs_within(balls, 1.0)
+    }
+
+    // the same with every radius scaled by `reach`, for interactions that act before the balls touch
+    pub fn pairs_within(&mut self, balls: &[Ball], reach: f32) -> &[(usize, usize)] {
+        self.pairs.clear();
+        if balls.is_empty() {
+            return &self.pairs;
+        }
+
+        let average = balls.iter().map(|b| b.radius * reach).sum::<f32>() / balls.len() as f32;
+        let cell = (average * 4.0).max(1.0);
+
+        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+        for ball in balls {
+            let radius = ball.radius * reach;
+            min_x = min_x.min(ball.center.x - radius);
+            min_y = min_y.min(ball.center.y - radius);
+            max_x = max_x.max(ball.center.x + radius);
+            max_y = max_y.max(ball.center.y + radius);
+        }
+
+        // a few huge balls could blow the grid up, so coarsen it past a cell per ball
+        let mut cell = cell;
+        while ((max_x - min_x) / cell + 1.0) * ((max_y - min_y) / cell + 1.0) > (balls.len() * 4) as f32 {
+            cell *= 2.0;
+        }
+
+        let columns = ((max_x - min_x) / cell) as usize + 1;
+        let rows = ((max_y - min_y) / cell) as usize + 1;
+        for members in &mut self.cells {
+            members.clear();
+        }
+        self.cells.resize(columns * rows, Vec::new());
+
+        let index = |x: f32, y: f32| (((x - min_x) / cell) as usize, ((y - min_y) / cell) as usize);
+        for (i, ball) in balls.iter().enumerate() {
+            let radius = ball.radius * reach;
+            let (x0, y0) = index(ball.center.x - radius, ball.center.y - radius);
+            let (x1, y1) = index(ball.center.x + radius, ball.center.y + radius);
+
+            for y in y0..=y1.min(rows - 1) {
+                for x in x0..=x1.min(columns - 1) {
+                    self.cells[y * columns + x].push(i);
+                }
+            }
+        }
+
+        for (c, members) in self.cells[..columns * rows].iter().enumerate() {
+            for (n, &i) in members.iter().enumerate() {
+                for &j in &members[n + 1..] {
+                    let (a, b) = (&balls[i], &balls[j]);
+                    let (ra, rb) = (a.radius * reach, b.radius * reach);
+                    if !overlaps(a.center, ra, b.center, rb) {
+                        continue;
+                    }
+
+                    // pairs sharing several cells are only reported by the one holding the corner of their overlap
+                    let corner_x = (a.center.x - ra).max(b.center.x - rb);
+                    let corner_y = (a.center.y - ra).max(b.center.y - rb);
+                    let (x, y) = index(corner_x, corner_y);
+                    if y * columns + x == c {
+                        self.pairs.push((i.min(j), i.max(j)));
+                    }
+                }
+            }
+        }
+
+        &self.pairs
+    }
+}
+
+fn overlaps(a: Vector2, ra: f32, b: Vector2, rb: f32) -> bool {
+    let reach = ra + rb;
+    (a.x - b.x).abs() <= reach && (a.y - b.y).abs() <= reach
+}
@@ -0,0 +1,53 @@
+const SLOW_SCALE: f32 = 0.15;
+// how long the world stays fully slowed, then how quickly it eases back to full speed
+const HOLD: f32 = 0.3;
+const RECOVERY: f32 = 2.0;
+
+// slows the world down for a moment after a hard impact, then eases back to real time;
+// the time scale multiplies the step, it is up to the caller to feed it the impacts
+pub struct BulletTime {
+    pub enabled: bool,
+    pub threshold: f32,
+    scale: f32,
+    hold: f32,
+}
+
+impl BulletTime {
+    pub fn new(threshold: f32) -> Self {
+        Self { enabled: false, threshold, scale: 1.0, hold: 0.0 }
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.scale = 1.0;
+        self.hold = 0.0;
+    }
+
+    // the impact threshold the world should report collisions above
+    pub fn impact_threshold(&self) -> Option<f32> {
+        self.enabled.then_some(self.threshold)
+    }
+
+    pub fn trigger(&mut self) {
+        if self.enabled {
+            self.scale = SLOW_SCALE;
+            self.hold = HOLD;
+        }
+    }
+
+    // advances by real time, so the recovery does not slow down with the world
+    pub fn update(&mut self, dt: f32) {
+        if self.hold > 0.0 {
+            self.hold -= dt;
+            return;
+        }
+        self.scale += (1.0 - self.scale) * (1.0 - (-RECOVERY * dt).exp());
+        if 1.0 - self.scale < 0.01 {
+            self.scale = 1.0;
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+}
@@ -0,0 +1,102 @@
+use raylib::prelude::*;
+
+use balls::world::World;
+use balls::{pick, Camera};
+
+use crate::hud::Hud;
+use crate::locale::Locale;
+
+const DROP_HEIGHT: f32 = 400.0;
+const BOUNCES: usize = 5;
+// rebounds lower than this are lost in the floor contact and end the run
+const MIN_REBOUND: f32 = 0.5;
+const MARK_COLOR: Color = Color::new(0, 140, 90, 255);
+
+// drops a ball from DROP_HEIGHT above the floor, or from under the ceiling in a shorter world, and measures
+// how high it comes back each time; heights are taken from the floor to the bottom of the ball
+#[derive(Default)]
+pub struct Calibration {
+    ball: Option<usize>,
+    floor: f32,
+    drop: f32,
+    peaks: Vec<f32>,
+    rising: bool,
+}
+
+impl Calibration {
+    pub fn click(&mut self, world: &mut World, point: Vector2) {
+        self.cancel();
+
+        let bounds = world.bounds;
+        if let Some(ball) = pick(&world.balls, point).and_then(|id| world.ball_mut(id)) {
+            self.floor = bounds.y;
+            self.drop = DROP_HEIGHT.min(bounds.height - 2.0 * ball.radius).max(0.0);
+            ball.center.y = self.floor + self.drop + ball.radius;
+            ball.velocity = Vector2::zero();
+            ball.set_static(false);
+            self.ball = Some(ball.id);
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.ball = None;
+        self.peaks.clear();
+        self.rising = false;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.peaks.len() >= BOUNCES || self.peaks.last().is_some_and(|&h| h < MIN_REBOUND)
+    }
+
+    // call once per step; an apex is wherever the ball stops rising
+    pub fn record(&mut self, world: &World) {
+        if self.is_done() {
+            return;
+        }
+        let Some(ball) = self.ball.and_then(|id| world.ball(id)) else {
+            return;
+        };
+
+        if self.rising && ball.velocity.y <= 0.0 {
+            self.peaks.push(ball.center.y - ball.radius - self.floor);
+        }
+        self.rising = ball.velocity.y > 0.0;
+    }
+
+    // restitution is the speed ratio of a bounce, i.e. the square root of the height ratio
+    pub fn bounces(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        let drops = std::iter::once(self.drop).chain(self.peaks.iter().copied());
+        drops.zip(&self.peaks).map(|(from, &to)| (to, (to / from).max(0.0).sqrt()))
+    }
+
+    pub fn effective_restitution(&self) -> Option<f32> {
+        let last = *self.peaks.last()?;
+        Some((last / self.drop).max(0.0).powf(0.5 / self.peaks.len() as f32))
+    }
+
+    pub fn draw(&self, world: &World, cam: &Camera, locale: &Locale, hud: &Hud, d: &mut RaylibDrawHandle) {
+        let Some(ball) = self.ball.and_then(|id| world.ball(id)) else {
+            hud.text(d, locale.text("calibration.hint"), 10, 40, MARK_COLOR);
+            return;
+        };
+
+        for height in std::iter::once(self.drop).chain(self.peaks.iter().copied()).map(|h| h + self.floor) {
+            let left = cam.project(Vector2::new(ball.center.x - ball.radius * 2.0, height));
+            let right = cam.project(Vector2::new(ball.center.x + ball.radius * 2.0, height));
+            d.draw_line_v(left, right, MARK_COLOR);
+        }
+
+        let material = locale.text(&format!("material.{}", ball.material.name)).to_string();
+        let mut lines = vec![locale.format("calibration.dropped", &[&ball.id, &material, &format!("{:.0}", self.drop)])];
+        for (i, (height, restitution)) in self.bounces().enumerate() {
+            lines.push(locale.format("calibration.bounce", &[&(i + 1), &format!("{:.1}", height), &format!("{:.3}", restitution)]));
+        }
+        if let Some(effective) = self.effective_restitution() {
+            lines.push(locale.format("calibration.effective", &[&format!("{:.3}", effective), &format!("{:.3}", ball.material.restitution)]));
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            hud.text(d, line, 10, 40 + 12 * i as i32, MARK_COLOR);
+        }
+    }
+}
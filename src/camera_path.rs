@@ -0,0 +1,173 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::snapshot::Vector2Def;
+use crate::Camera;
+
+// the camera looks at `center` with `zoom` at `time` seconds into the scene; zoom is positive
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "Key")]
+#[non_exhaustive]
+pub struct Keyframe {
+    time: f32,
+    #[serde(with = "Vector2Def")]
+    center: Vector2,
+    zoom: f32,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, center: Vector2, zoom: f32) -> Result<Self, String> {
+        if !zoom.is_finite() || zoom <= 0.0 {
+            return Err(format!("a camera keyframe's zoom must be positive, got {}", zoom));
+        }
+        Ok(Self { time, center, zoom })
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn center(&self) -> Vector2 {
+        self.center
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+}
+
+// a keyframe as scene files hold it, checked before it is used
+#[derive(Deserialize)]
+struct Key {
+    time: f32,
+    #[serde(with = "Vector2Def")]
+    center: Vector2,
+    zoom: f32,
+}
+
+impl TryFrom<Key> for Keyframe {
+    type Error = String;
+
+    fn try_from(key: Key) -> Result<Self, String> {
+        Self::new(key.time, key.center, key.zoom)
+    }
+}
+
+// keyframed camera moves, eased through with catmull-rom splines so the motion has no kinks at the keys, even
+// where they are spaced unevenly;
+// holds the last keyframe once the path runs out
+#[derive(Default)]
+pub struct CameraPath {
+    keys: Vec<Keyframe>,
+    time: f32,
+    pub playing: bool,
+}
+
+impl CameraPath {
+    pub fn new(mut keys: Vec<Keyframe>) -> Self {
+        keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let playing = !keys.is_empty();
+
+        Self { keys, time: 0.0, playing }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keys.last().map_or(0.0, |k| k.time)
+    }
+
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+        self.playing = !self.keys.is_empty();
+    }
+
+    pub fn advance(&mut self, dt: f32, cam: &mut Camera, screen: Vector2) {
+        if !self.playing {
+            return;
+        }
+
+        self.time += dt;
+        if let Some((center, zoom)) = self.sample(self.time) {
+            cam.look_at(center, zoom, screen);
+        }
+        self.playing = self.time < self.duration();
+    }
+
+    pub fn sample(&self, time: f32) -> Option<(Vector2, f32)> {
+        let first = self.keys.first()?;
+        let last = self.keys.last()?;
+        if time <= first.time || self.keys.len() == 1 {
+            return Some((first.center, first.zoom));
+        }
+        if time >= last.time {
+            return Some((last.center, last.zoom));
+        }
+
+        let i = self.keys.iter().rposition(|k| k.time <= time)?;
+        let key = |j: isize| self.keys[j.clamp(0, self.keys.len() as isize - 1) as usize];
+        let (k0, k1, k2, k3) = (key(i as isize - 1), key(i as isize), key(i as isize + 1), key(i as isize + 2));
+
+        let times = [k0.time, k1.time, k2.time, k3.time];
+        let ease = |value: fn(&Keyframe) -> f32| catmull_rom([k0, k1, k2, k3].map(|k| value(&k)), times, time);
+
+        let center = Vector2::new(ease(|k| k.center.x), ease(|k| k.center.y));
+        // zoom is eased in log space so doubling and halving take the same time
+        let zoom = ease(|k| k.zoom.ln()).exp();
+
+        Some((center, zoom))
+    }
+}
+
+// the span between the middle two of four keys, with the tangents at both ends taken over the key times
+// around them rather than assuming the keys are a second apart
+fn catmull_rom(p: [f32; 4], times: [f32; 4], time: f32) -> f32 {
+    let tangent = |a: usize, b: usize| (p[b] - p[a]) / (times[b] - times[a]).max(f32::EPSILON);
+    let span = (times[2] - times[1]).max(f32::EPSILON);
+    let (m1, m2) = (tangent(0, 2) * span, tangent(1, 3) * span);
+
+    let t = (time - times[1]) / span;
+    let (t2, t3) = (t * t, t * t * t);
+    (2.0 * t3 - 3.0 * t2 + 1.0) * p[1] + (t3 - 2.0 * t2 + t) * m1 + (3.0 * t2 - 2.0 * t3) * p[2] + (t3 - t2) * m2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_keyframes_without_a_positive_zoom_are_rejected() {
+        for zoom in ["0.0", "-2.0"] {
+            let json = format!(r#"{{ "time": 1.0, "center": {{ "x": 0.0, "y": 0.0 }}, "zoom": {} }}"#, zoom);
+            assert!(serde_json::from_str::<Keyframe>(&json).is_err());
+        }
+        let json = r#"{ "time": 1.0, "center": { "x": 0.0, "y": 0.0 }, "zoom": 2.0 }"#;
+        assert_eq!(serde_json::from_str::<Keyframe>(json).unwrap().zoom(), 2.0);
+        assert!(Keyframe::new(1.0, Vector2::zero(), 0.0).is_err());
+    }
+
+    #[test]
+    fn zoom_eases_in_log_space() {
+        let key = |time, zoom| Keyframe::new(time, Vector2::zero(), zoom).unwrap();
+        let path = CameraPath::new(vec![key(1.0, 4.0), key(0.0, 1.0)]);
+        let (_, zoom) = path.sample(0.5).unwrap();
+        assert!((zoom - 2.0).abs() < 1e-4);
+        assert_eq!(path.sample(5.0).unwrap().1, 4.0);
+    }
+
+    #[test]
+    fn a_steady_pan_stays_steady_across_uneven_keys() {
+        let key = |time: f32| Keyframe::new(time, Vector2::new(10.0 * time, 0.0), 1.0).unwrap();
+        let path = CameraPath::new(vec![key(0.0), key(1.0), key(3.0), key(3.5)]);
+        for time in [0.5, 2.0, 2.5, 3.2] {
+            let (center, _) = path.sample(time).unwrap();
+            assert!((center.x - 10.0 * time).abs() < 1e-3, "{} at {}", center.x, time);
+        }
+    }
+}
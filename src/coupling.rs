@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CouplingKind {
+    // keeps `first spin + ratio * second spin` at zero, so meshed hinges turn in opposite directions
+    Gear,
+    // keeps `first length + ratio * second length` from exceeding `total`, like a rope over two wheels
+    Pulley { total: f32 },
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Coupling {
+    pub id: usize,
+    pub first: usize,
+    pub second: usize,
+    pub ratio: f32,
+    pub kind: CouplingKind,
+}
+
+impl Coupling {
+    pub fn gear(first: usize, second: usize, ratio: f32) -> Self {
+        Self { id: 0, first, second, ratio, kind: CouplingKind::Gear }
+    }
+
+    pub fn pulley(first: usize, second: usize, ratio: f32, total: f32) -> Self {
+        Self { id: 0, first, second, ratio, kind: CouplingKind::Pulley { total } }
+    }
+
+    pub fn couples(&self, joint: usize) -> bool {
+        self.first == joint || self.second == joint
+    }
+}
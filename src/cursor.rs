@@ -0,0 +1,61 @@
+use raylib::prelude::*;
+
+use balls::render::Renderer;
+use balls::world::World;
+use balls::{Ball, Camera};
+
+const CURSOR_COLOR: Color = Color::new(0, 160, 255, 160);
+const MIN_RADIUS: f32 = 5.0;
+const MAX_RADIUS: f32 = 120.0;
+
+// the mouse as a kinematic circle: it follows the pointer exactly and shoves balls out of its way,
+// never being pushed back itself
+pub struct Cursor {
+    pub enabled: bool,
+    pub radius: f32,
+    last: Option<Vector2>,
+}
+
+impl Cursor {
+    pub fn new(radius: f32) -> Self {
+        Self { enabled: false, radius: radius.clamp(MIN_RADIUS, MAX_RADIUS), last: None }
+    }
+
+    pub fn toggle(&mut self, world: &mut World) {
+        self.enabled = !self.enabled;
+        self.release(world);
+    }
+
+    pub fn resize(&mut self, delta: f32) {
+        self.radius = (self.radius + delta).clamp(MIN_RADIUS, MAX_RADIUS);
+    }
+
+    // places the world's kinematic body on `point`, moving at the speed the pointer moved since the last frame
+    pub fn update(&mut self, world: &mut World, point: Vector2, dt: f32) {
+        if !self.enabled {
+            return;
+        }
+
+        let velocity = match self.last {
+            Some(last) if dt > 0.0 => (point - last) / dt,
+            _ => Vector2::zero(),
+        };
+        self.last = Some(point);
+
+        world.kinematic.clear();
+        world.kinematic.push(Ball::new(point, self.radius, CURSOR_COLOR).with_static().with_velocity(velocity));
+    }
+
+    // drops the body, so the cursor stops pushing while something else owns the mouse
+    pub fn release(&mut self, world: &mut World) {
+        self.last = None;
+        world.kinematic.clear();
+    }
+
+    pub fn draw(&self, world: &World, cam: &Camera, d: &mut impl Renderer) {
+        for body in &world.kinematic {
+            let radius = cam.scale(body.radius);
+            d.ring(cam.project(body.center), radius - 2.0, radius, CURSOR_COLOR);
+        }
+    }
+}
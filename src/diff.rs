@@ -0,0 +1,94 @@
+use balls::scene::Scene;
+use balls::Ball;
+
+use crate::arg;
+
+const TOLERANCE: f32 = 1e-4;
+
+// `diff <a> <b> [--tolerance <t>]` compares two scene or snapshot files ball by ball, matching
+// balls by id; returns whether they agree, so the exit code can be used like diff's
+pub fn run(args: &[String]) -> bool {
+    let [a_path, b_path] = paths(&args[2..])[..] else {
+        eprintln!("usage: diff <a.json> <b.json> [--tolerance <t>]");
+        return false;
+    };
+    let tolerance = arg(args, "--tolerance").and_then(|v| v.parse::<f32>().ok()).unwrap_or(TOLERANCE);
+
+    let load = |path: &str| Scene::load(path).map_err(|err| eprintln!("could not load {}: {}", path, err)).ok();
+    let (Some(a), Some(b)) = (load(a_path), load(b_path)) else {
+        return false;
+    };
+
+    let report = diff(&a.world.balls, &b.world.balls, tolerance);
+    for line in &report {
+        println!("{}", line);
+    }
+    if a.world.joints.len() != b.world.joints.len() {
+        println!("joints: {} vs {}", a.world.joints.len(), b.world.joints.len());
+    }
+
+    let differing = report.len();
+    if differing == 0 && a.world.joints.len() == b.world.joints.len() {
+        println!("{} balls match within {}", a.world.balls.len(), tolerance);
+        true
+    } else {
+        println!("{} of {} balls differ", differing, a.world.balls.len().max(b.world.balls.len()));
+        false
+    }
+}
+
+// the arguments that are not flags or their values, so the flags can go anywhere
+fn paths(args: &[String]) -> Vec<&str> {
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            args.next();
+        } else {
+            paths.push(arg.as_str());
+        }
+    }
+    paths
+}
+
+// one line per ball that is missing from either side or moved or sped up by more than `tolerance`
+pub fn diff(a: &[Ball], b: &[Ball], tolerance: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for ball in a {
+        let Some(other) = b.iter().find(|o| o.id == ball.id) else {
+            lines.push(format!("#{}: only in the first file", ball.id));
+            continue;
+        };
+
+        let position = ball.center.distance_to(other.center);
+        let velocity = ball.velocity.distance_to(other.velocity);
+        if position > tolerance || velocity > tolerance {
+            lines.push(format!(
+                "#{}: position ({:.4}, {:.4}) vs ({:.4}, {:.4}), off by {:.6}; velocity ({:.4}, {:.4}) vs ({:.4}, {:.4}), off by {:.6}",
+                ball.id,
+                ball.center.x, ball.center.y, other.center.x, other.center.y, position,
+                ball.velocity.x, ball.velocity.y, other.velocity.x, other.velocity.y, velocity
+            ));
+        }
+    }
+
+    for ball in b.iter().filter(|o| !a.iter().any(|ball| ball.id == o.id)) {
+        lines.push(format!("#{}: only in the second file", ball.id));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_can_come_before_the_paths() {
+        let args: Vec<String> = ["--tolerance", "0.1", "a.json", "b.json"].iter().map(|a| a.to_string()).collect();
+        assert_eq!(paths(&args), ["a.json", "b.json"]);
+        let args: Vec<String> = ["a.json", "--tolerance", "0.1", "b.json"].iter().map(|a| a.to_string()).collect();
+        assert_eq!(paths(&args), ["a.json", "b.json"]);
+    }
+}
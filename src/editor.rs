@@ -0,0 +1,209 @@
+use std::ffi::CString;
+
+use raylib::prelude::*;
+
+use balls::joint::{Joint, JointKind};
+use balls::probe::{Probe, ProbeShape, PROBE_COLOR};
+use balls::render::Renderer;
+use balls::world::World;
+use balls::{pick, Camera};
+
+use crate::locale::Locale;
+
+const PENDING_COLOR: Color = Color::new(0, 160, 255, 255);
+const JOINT_PICK_TOLERANCE: f32 = 6.0;
+// shorter drags are clicks, not probes
+const DRAG_THRESHOLD: f32 = 4.0;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Tool {
+    Select,
+    Connect,
+    Calibrate,
+    Probe,
+    Launch,
+}
+
+impl Tool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Select => "select",
+            Tool::Connect => "connect",
+            Tool::Calibrate => "calibrate",
+            Tool::Probe => "probe",
+            Tool::Launch => "launch",
+        }
+    }
+
+    pub fn next(&self) -> Tool {
+        match self {
+            Tool::Select => Tool::Connect,
+            Tool::Connect => Tool::Calibrate,
+            Tool::Calibrate => Tool::Probe,
+            Tool::Probe => Tool::Launch,
+            Tool::Launch => Tool::Select,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ConnectTool {
+    first: Option<usize>,
+}
+
+impl ConnectTool {
+    // first click picks a ball, the second one opens the popup; clicking a joint deletes it
+    pub fn click(&mut self, world: &mut World, point: Vector2, cam: &Camera) -> Option<JointPopup> {
+        let picked = pick(&world.balls, point);
+
+        match (self.first, picked) {
+            (Some(a), Some(b)) if a != b => {
+                self.first = None;
+                let length = world.ball(a)?.center.distance_to(world.ball(b)?.center);
+                Some(JointPopup::new(a, b, length))
+            }
+            (_, Some(id)) => {
+                self.first = Some(id);
+                None
+            }
+            (_, None) => {
+                self.first = None;
+                if let Some(id) = world.joint_at(point, JOINT_PICK_TOLERANCE / cam.scale(1.0)) {
+                    world.remove_joint(id);
+                }
+                None
+            }
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.first = None;
+    }
+
+    pub fn draw(&self, world: &World, cam: &Camera, d: &mut RaylibDrawHandle) {
+        if let Some(ball) = self.first.and_then(|id| world.ball(id)) {
+            ball.draw_selection(cam, PENDING_COLOR, d);
+            d.draw_line_v(cam.project(ball.center), d.get_mouse_position(), PENDING_COLOR);
+        }
+    }
+}
+
+pub enum PopupResult {
+    Open,
+    Cancel,
+    Create(Joint),
+}
+
+pub struct JointPopup {
+    a: usize,
+    b: usize,
+    kind: usize,
+    length: f32,
+    stiffness: f32,
+    damping: f32,
+    break_impulse: f32,
+}
+
+impl JointPopup {
+    pub fn new(a: usize, b: usize, length: f32) -> Self {
+        Self {
+            a,
+            b,
+            kind: 0,
+            length,
+            stiffness: 1000.0,
+            damping: 10.0,
+            break_impulse: 0.0,
+        }
+    }
+
+    pub fn draw(&mut self, locale: &Locale, d: &mut RaylibDrawHandle) -> PopupResult {
+        let (x, y) = (200.0, 150.0);
+        let title = CString::new(locale.format("popup.title", &[&self.a, &self.b])).unwrap();
+        let kinds = CString::new(locale.text("popup.kinds")).unwrap();
+
+        if d.gui_window_box(Rectangle::new(x, y, 240.0, 194.0), Some(&title)) {
+            return PopupResult::Cancel;
+        }
+
+        self.kind = d.gui_toggle_group(Rectangle::new(x + 10.0, y + 34.0, 72.0, 20.0), Some(&kinds), self.kind as i32) as usize;
+
+        self.length = slider(d, x, y + 64.0, locale.text("popup.length"), self.length, 0.0, 400.0);
+        // zero means the joint never breaks
+        self.break_impulse = slider(d, x, y + 88.0, locale.text("popup.break"), self.break_impulse, 0.0, 20000.0);
+        if self.kind == 1 {
+            self.stiffness = slider(d, x, y + 112.0, locale.text("popup.stiffness"), self.stiffness, 10.0, 5000.0);
+            self.damping = slider(d, x, y + 136.0, locale.text("popup.damping"), self.damping, 0.0, 100.0);
+        }
+
+        if d.gui_button(Rectangle::new(x + 10.0, y + 164.0, 105.0, 20.0), Some(&CString::new(locale.text("popup.create")).unwrap())) {
+            return PopupResult::Create(self.joint());
+        }
+        if d.gui_button(Rectangle::new(x + 125.0, y + 164.0, 105.0, 20.0), Some(&CString::new(locale.text("popup.cancel")).unwrap())) {
+            return PopupResult::Cancel;
+        }
+
+        PopupResult::Open
+    }
+
+    fn joint(&self) -> Joint {
+        let kind = match self.kind {
+            1 => JointKind::Spring { stiffness: self.stiffness, damping: self.damping },
+            2 => JointKind::Rope,
+            _ => JointKind::Distance,
+        };
+
+        let joint = Joint::new(self.a, self.b, self.length, kind);
+        if self.break_impulse > 0.0 {
+            joint.with_break_impulse(self.break_impulse)
+        } else {
+            joint
+        }
+    }
+}
+
+fn slider(d: &mut RaylibDrawHandle, x: f32, y: f32, label: &str, value: f32, min: f32, max: f32) -> f32 {
+    let label = CString::new(label).unwrap();
+    let text = CString::new(format!("{:.0}", value)).unwrap();
+
+    d.gui_slider_bar(Rectangle::new(x + 70.0, y, 120.0, 16.0), Some(&label), Some(&text), value, min, max)
+}
+
+// dragging lays a line probe, or a region probe with shift held
+#[derive(Default)]
+pub struct ProbeTool {
+    start: Option<Vector2>,
+}
+
+impl ProbeTool {
+    pub fn begin_drag(&mut self, screen: Vector2) {
+        self.start = Some(screen);
+    }
+
+    pub fn end_drag(&mut self, screen: Vector2, cam: &Camera, region: bool, probes: &mut Vec<Probe>) {
+        let Some(start) = self.start.take() else {
+            return;
+        };
+        if start.distance_to(screen) < DRAG_THRESHOLD {
+            return;
+        }
+
+        let (a, b) = (cam.unproject(start), cam.unproject(screen));
+        let shape = if region {
+            ProbeShape::Region { area: Rectangle::new(a.x.min(b.x), a.y.min(b.y), (a.x - b.x).abs(), (a.y - b.y).abs()) }
+        } else {
+            ProbeShape::Line { from: a, to: b }
+        };
+        probes.push(Probe::new(format!("probe {}", probes.len() + 1), shape));
+    }
+
+    pub fn cancel(&mut self) {
+        self.start = None;
+    }
+
+    pub fn draw(&self, mouse: Vector2, d: &mut impl Renderer) {
+        if let Some(start) = self.start {
+            d.line(start, mouse, 1.0, PROBE_COLOR);
+        }
+    }
+}
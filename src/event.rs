@@ -0,0 +1,13 @@
+use raylib::prelude::*;
+
+use crate::bounce::BounceRule;
+
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum Event {
+    JointBroken { joint: usize, a: usize, b: usize, position: Vector2, impulse: f32 },
+    // a collision whose impulse exceeded the world's `impact_threshold`
+    Impact { a: usize, b: usize, position: Vector2, impulse: f32 },
+    // a ball hit the walls as often as its bounce limit allows; despawned balls are already gone
+    BouncesExhausted { ball: usize, position: Vector2, rule: BounceRule },
+}
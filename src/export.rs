@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use raylib::prelude::*;
+
+use crate::coupling::CouplingKind;
+use crate::joint::JointKind;
+use crate::material::Material;
+use crate::world::World;
+
+// emits the world as builder calls that rebuild it, for pasting into tests and examples;
+// balls and joints only get a binding when something else refers to them
+pub fn rust_snippet(world: &World) -> String {
+    let mut out = String::new();
+    let defaults = World::new();
+
+    out.push_str("let mut world = World::new();\n");
+    if world.gravity != defaults.gravity {
+        writeln!(out, "world.gravity = {};", vector(world.gravity)).unwrap();
+    }
+    let bounds = world.bounds;
+    if (bounds.x, bounds.y, bounds.width, bounds.height) != (defaults.bounds.x, defaults.bounds.y, defaults.bounds.width, defaults.bounds.height) {
+        writeln!(out, "world.bounds = Rectangle::new({:?}, {:?}, {:?}, {:?});", bounds.x, bounds.y, bounds.width, bounds.height).unwrap();
+    }
+    if world.collision_iterations != defaults.collision_iterations {
+        writeln!(out, "world.collision_iterations = {};", world.collision_iterations).unwrap();
+    }
+    if world.joint_iterations != defaults.joint_iterations {
+        writeln!(out, "world.joint_iterations = {};", world.joint_iterations).unwrap();
+    }
+    for (name, value, default) in [
+        ("damping", world.damping, defaults.damping),
+        ("freezing_threshold", world.freezing_threshold, defaults.freezing_threshold),
+        ("impact_speed", world.impact_speed, defaults.impact_speed),
+        ("magnet_range", world.magnet_range, defaults.magnet_range),
+    ] {
+        if value != default {
+            writeln!(out, "world.{} = {:?};", name, value).unwrap();
+        }
+    }
+    if world.drag != defaults.drag {
+        writeln!(out, "world.drag = {:?};", world.drag).unwrap();
+    }
+    if world.wrap_x {
+        out.push_str("world.wrap_x = true;\n");
+    }
+    if world.wrap_y {
+        out.push_str("world.wrap_y = true;\n");
+    }
+    if world.thermostat.temperature > 0.0 {
+        writeln!(out, "world.thermostat.temperature = {:?};", world.thermostat.temperature).unwrap();
+    }
+    if let Some(limit) = world.bounce_limit {
+        writeln!(out, "world.bounce_limit = Some(BounceLimit::new({}, BounceRule::{:?}));", limit.count, limit.rule).unwrap();
+    }
+
+    for field in &world.fields {
+        let area = field.area;
+        writeln!(
+            out,
+            "world.fields.push(ForceField::new(Rectangle::new({:?}, {:?}, {:?}, {:?}), {}));",
+            area.x, area.y, area.width, area.height, vector(field.acceleration)
+        )
+        .unwrap();
+    }
+
+    for zone in &world.time_zones {
+        let area = zone.area;
+        writeln!(
+            out,
+            "world.time_zones.push(TimeZone::new(Rectangle::new({:?}, {:?}, {:?}, {:?}), {:?}));",
+            area.x, area.y, area.width, area.height, zone.scale
+        )
+        .unwrap();
+    }
+
+    if let Some(terrain) = &world.terrain {
+        let heights: Vec<String> = terrain.heights().iter().map(|h| format!("{:?}", h)).collect();
+        writeln!(
+            out,
+            "world.terrain = Some(Heightfield::new({:?}, {:?}, vec![{}], {:?}).unwrap());",
+            terrain.left(), terrain.spacing(), heights.join(", "), terrain.friction
+        )
+        .unwrap();
+    }
+
+    let bound_balls: HashSet<usize> = world.joints.iter()
+        .flat_map(|j| [j.a, j.b])
+        .chain(world.thrusters.iter().map(|t| t.ball))
+        .chain(world.tags.values().copied())
+        .collect();
+    for ball in &world.balls {
+        let mut call = format!("world.add_ball(Ball::new({}, {:?}, {})", vector(ball.center), ball.radius, color(ball.color));
+        if ball.material != Material::default() {
+            write!(call, ".with_material({})", material(&ball.material)).unwrap();
+        }
+        if ball.velocity != Vector2::zero() {
+            write!(call, ".with_velocity({})", vector(ball.velocity)).unwrap();
+        }
+        if ball.is_static {
+            call.push_str(".with_static()");
+        }
+        if let Some(magnet) = ball.magnet {
+            write!(call, ".with_magnet({:?}, {:?})", magnet.moment, magnet.angle).unwrap();
+        }
+        if let Some(pulse) = ball.pulse {
+            write!(call, ".with_pulse(Pulse::{:?})", pulse).unwrap();
+        }
+        if let Some(limit) = ball.bounce_limit {
+            write!(call, ".with_bounce_limit({}, BounceRule::{:?})", limit.count, limit.rule).unwrap();
+        }
+        call.push(')');
+
+        if bound_balls.contains(&ball.id) {
+            writeln!(out, "let b{} = {};", ball.id, call).unwrap();
+            if let Some(tag) = world.tag_of(ball.id) {
+                writeln!(out, "world.tag_ball(b{}, {:?});", ball.id, tag).unwrap();
+            }
+        } else {
+            writeln!(out, "{};", call).unwrap();
+        }
+    }
+
+    let bound_joints: HashSet<usize> = world
+        .joints
+        .iter()
+        .filter_map(|j| j.parent)
+        .chain(world.couplings.iter().flat_map(|c| [c.first, c.second]))
+        .collect();
+    for joint in &world.joints {
+        let kind = match joint.kind {
+            JointKind::Distance => "JointKind::Distance".to_string(),
+            JointKind::Spring { stiffness, damping } => format!("JointKind::Spring {{ stiffness: {:?}, damping: {:?} }}", stiffness, damping),
+            JointKind::Rope => "JointKind::Rope".to_string(),
+        };
+        let mut call = format!("world.add_joint(Joint::new(b{}, b{}, {:?}, {})", joint.a, joint.b, joint.length, kind);
+        if let Some(parent) = joint.parent {
+            write!(call, ".with_parent(j{})", parent).unwrap();
+        }
+        if joint.reference != 0.0 {
+            write!(call, ".with_reference({:?})", joint.reference).unwrap();
+        }
+        if let Some(limit) = joint.limit {
+            write!(call, ".with_limit({:?}, {:?})", limit.min, limit.max).unwrap();
+        }
+        if let Some(motor) = joint.motor {
+            write!(call, ".with_motor({:?}, {:?})", motor.speed, motor.max_torque).unwrap();
+        }
+        if let Some(impulse) = joint.break_impulse {
+            write!(call, ".with_break_impulse({:?})", impulse).unwrap();
+        }
+        call.push(')');
+
+        if bound_joints.contains(&joint.id) {
+            writeln!(out, "let j{} = {};", joint.id, call).unwrap();
+        } else {
+            writeln!(out, "{};", call).unwrap();
+        }
+    }
+
+    for thruster in &world.thrusters {
+        writeln!(out, "world.thrusters.push(Thruster::new(b{}, {:?}, {:?}));", thruster.ball, thruster.angle, thruster.force).unwrap();
+    }
+
+    for coupling in &world.couplings {
+        let call = match coupling.kind {
+            CouplingKind::Gear => format!("Coupling::gear(j{}, j{}, {:?})", coupling.first, coupling.second, coupling.ratio),
+            CouplingKind::Pulley { total } => format!("Coupling::pulley(j{}, j{}, {:?}, {:?})", coupling.first, coupling.second, coupling.ratio, total),
+        };
+        writeln!(out, "world.add_coupling({});", call).unwrap();
+    }
+
+    out
+}
+
+fn vector(v: Vector2) -> String {
+    format!("Vector2::new({:?}, {:?})", v.x, v.y)
+}
+
+fn color(c: Color) -> String {
+    format!("Color::new({}, {}, {}, {})", c.r, c.g, c.b, c.a)
+}
+
+fn material(m: &Material) -> String {
+    if Material::PRESETS.contains(m) {
+        format!("Material::{}", m.name.to_uppercase())
+    } else {
+        format!(
+            "Material {{ name: {:?}, restitution: {:?}, density: {:?}, friction: {:?} }}",
+            m.name, m.restitution, m.density, m.friction
+        )
+    }
+}
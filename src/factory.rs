@@ -0,0 +1,230 @@
+use std::f32::consts::PI;
+
+use raylib::prelude::*;
+
+use crate::coupling::Coupling;
+use crate::joint::{Joint, JointKind};
+use crate::world::World;
+use crate::Ball;
+
+const CHAIN_COLOR: Color = Color::new(120, 90, 60, 255);
+const RAGDOLL_COLOR: Color = Color::new(230, 170, 130, 255);
+const MACHINE_COLOR: Color = Color::new(90, 100, 120, 255);
+const BRIDGE_COLOR: Color = Color::new(150, 110, 70, 255);
+const FLIPPER_SWING: f32 = 1.0;
+const FLIPPER_TORQUE: f32 = 1e9;
+const WINDMILL_TORQUE: f32 = 1e8;
+
+// hangs a chain of balls below a static anchor, each link allowed to bend by at most `max_bend`
+pub fn chain(world: &mut World, anchor: Vector2, links: usize, radius: f32, max_bend: f32) -> Vec<usize> {
+    let spacing = radius * 2.5;
+    let mut balls = vec![world.add_ball(Ball::new(anchor, radius, CHAIN_COLOR).with_static())];
+
+    let mut parent = None;
+    for i in 1..=links {
+        let center = anchor - Vector2::new(0.0, spacing * i as f32);
+        let ball = world.spawn(center, radius, CHAIN_COLOR);
+
+        let mut joint = Joint::new(balls[i - 1], ball, spacing, JointKind::Distance);
+        if let Some(parent) = parent {
+            joint = joint.with_parent(parent).with_limit(-max_bend, max_bend);
+        }
+
+        parent = Some(world.add_joint(joint));
+        balls.push(ball);
+    }
+
+    balls
+}
+
+// a rope of balls slung between two static posts, each link snapping past `break_impulse`
+pub fn bridge(world: &mut World, from: Vector2, to: Vector2, radius: f32, break_impulse: f32) -> Vec<usize> {
+    let spacing = radius * 2.2;
+    let links = (from.distance_to(to) / spacing).ceil() as usize;
+    let step = (to - from) / links as f32;
+
+    let mut balls: Vec<usize> = Vec::new();
+    for i in 0..=links {
+        let mut ball = Ball::new(from + step * i as f32, radius, BRIDGE_COLOR);
+        if i == 0 || i == links {
+            ball = ball.with_static();
+        }
+        let ball = world.add_ball(ball);
+
+        if let Some(&prev) = balls.last() {
+            let joint = Joint::new(prev, ball, step.length(), JointKind::Distance).with_break_impulse(break_impulse);
+            world.add_joint(joint);
+        }
+        balls.push(ball);
+    }
+
+    balls
+}
+
+// a stick figure centered on the pelvis, `size` being the length of the spine
+pub fn ragdoll(world: &mut World, pelvis: Vector2, size: f32) -> Vec<usize> {
+    let radius = size * 0.15;
+    let limb = size * 0.6;
+
+    let bone = |world: &mut World, from: usize, to: Vector2, r: f32, parent: Option<(usize, f32, f32)>| {
+        let start = world.ball(from).unwrap().center;
+        let ball = world.spawn(to, r, RAGDOLL_COLOR);
+
+        let mut joint = Joint::new(from, ball, start.distance_to(to), JointKind::Distance);
+        if let Some((parent, min, max)) = parent {
+            joint = joint.with_parent(parent).with_limit(min, max);
+        }
+
+        (ball, world.add_joint(joint))
+    };
+
+    let pelvis_ball = world.spawn(pelvis, radius, RAGDOLL_COLOR);
+    let (chest, spine) = bone(world, pelvis_ball, pelvis + Vector2::new(0.0, size), radius, None);
+    let (head, _) = bone(world, chest, pelvis + Vector2::new(0.0, size * 1.6), radius * 1.6, Some((spine, -PI / 6.0, PI / 6.0)));
+
+    let mut balls = vec![pelvis_ball, chest, head];
+
+    for side in [-1.0, 1.0] {
+        // shoulders swing freely, elbows bend in one direction only
+        let elbow_at = pelvis + Vector2::new(side * limb * 0.7, size * 0.6);
+        let (elbow, upper_arm) = bone(world, chest, elbow_at, radius * 0.8, None);
+        let (hand, _) = bone(world, elbow, elbow_at + Vector2::new(side * limb * 0.3, -limb * 0.6), radius * 0.8, Some((upper_arm, -side.max(0.0) * PI * 0.8, -side.min(0.0) * PI * 0.8)));
+
+        // hips swing to the sides of the spine, knees only bend backwards
+        let knee_at = pelvis + Vector2::new(side * limb * 0.3, -limb);
+        let (knee, thigh) = bone(world, pelvis_ball, knee_at, radius, Some((spine, -PI / 2.0, PI / 2.0)));
+        let (foot, _) = bone(world, knee, knee_at - Vector2::new(0.0, limb), radius, Some((thigh, -PI * 0.7, PI / 8.0)));
+
+        balls.extend([elbow, hand, knee, foot]);
+    }
+
+    balls
+}
+
+// a flipper arm hinged on a static pivot, pointing right for `facing` 1 and left for -1;
+// returns the arm joints so their motors can be driven with `drive_flipper`
+pub fn flipper(world: &mut World, pivot: Vector2, length: f32, facing: f32) -> Vec<usize> {
+    let radius = 8.0;
+    let hub = world.add_ball(Ball::new(pivot, radius, MACHINE_COLOR).with_static());
+
+    // resting slightly below horizontal, swinging up by FLIPPER_SWING
+    let rest = if facing > 0.0 { -0.5 } else { PI + 0.5 };
+    let (min, max) = if facing > 0.0 { (0.0, FLIPPER_SWING) } else { (-FLIPPER_SWING, 0.0) };
+
+    let count = (length / (radius * 2.2)) as usize;
+    (1..=count)
+        .map(|i| {
+            let distance = radius * 2.2 * i as f32;
+            let ball = world.spawn(pivot + Vector2::new(rest.cos(), rest.sin()) * distance, radius, MACHINE_COLOR);
+            let joint = Joint::new(hub, ball, distance, JointKind::Distance)
+                .with_reference(rest)
+                .with_limit(min, max)
+                .with_motor(0.0, FLIPPER_TORQUE);
+
+            world.add_joint(joint)
+        })
+        .collect()
+}
+
+pub fn drive_flipper(world: &mut World, joints: &[usize], facing: f32, pressed: bool) {
+    let speed = facing * if pressed { 15.0 } else { -5.0 };
+
+    for joint in world.joints.iter_mut().filter(|j| joints.contains(&j.id)) {
+        if let Some(motor) = joint.motor.as_mut() {
+            motor.speed = speed;
+        }
+    }
+}
+
+// motor-driven arms around a static hub, neighbouring arms braced tip to tip
+pub fn windmill(world: &mut World, center: Vector2, arms: usize, length: f32, speed: f32) -> Vec<usize> {
+    let radius = 8.0;
+    let hub = world.add_ball(Ball::new(center, radius, MACHINE_COLOR).with_static());
+
+    let count = (length / (radius * 2.2)) as usize;
+    let mut joints = Vec::new();
+    let mut tips = Vec::new();
+
+    for arm in 0..arms {
+        let angle = PI * 2.0 * arm as f32 / arms as f32;
+        let direction = Vector2::new(angle.cos(), angle.sin());
+
+        for i in 1..=count {
+            let distance = radius * 2.2 * i as f32;
+            let ball = world.spawn(center + direction * distance, radius, MACHINE_COLOR);
+            let joint = Joint::new(hub, ball, distance, JointKind::Distance)
+                .with_reference(angle)
+                .with_motor(speed, WINDMILL_TORQUE);
+
+            joints.push(world.add_joint(joint));
+            if i == count {
+                tips.push(ball);
+            }
+        }
+    }
+
+    for i in 0..tips.len() {
+        let (a, b) = (tips[i], tips[(i + 1) % tips.len()]);
+        if a != b {
+            let length = world.ball(a).unwrap().center.distance_to(world.ball(b).unwrap().center);
+            world.add_joint(Joint::new(a, b, length, JointKind::Distance));
+        }
+    }
+
+    joints
+}
+
+// a motorized gear meshed with a free one, the second turning `ratio` times slower
+pub fn gears(world: &mut World, center: Vector2, radius: f32, ratio: f32, speed: f32) -> (Vec<usize>, Vec<usize>) {
+    let driver = gear(world, center - Vector2::new(radius, 0.0), radius, Some(speed));
+    let driven = gear(world, center + Vector2::new(radius * ratio, 0.0), radius * ratio, None);
+
+    world.add_coupling(Coupling::gear(driver[0], driven[0], ratio));
+    (driver, driven)
+}
+
+// spokes out to a braced rim of balls around a static hub; returns the spoke joints
+fn gear(world: &mut World, center: Vector2, radius: f32, motor: Option<f32>) -> Vec<usize> {
+    let hub = world.add_ball(Ball::new(center, 6.0, MACHINE_COLOR).with_static());
+
+    let teeth = ((radius / 8.0) as usize).max(3);
+    let tooth = radius * PI / teeth as f32 * 0.4;
+    let mut spokes = Vec::new();
+    let mut rim: Vec<usize> = Vec::new();
+
+    for i in 0..teeth {
+        let angle = PI * 2.0 * i as f32 / teeth as f32;
+        let ball = world.spawn(center + Vector2::new(angle.cos(), angle.sin()) * radius, tooth, MACHINE_COLOR);
+
+        let mut joint = Joint::new(hub, ball, radius, JointKind::Distance).with_reference(angle);
+        if let Some(speed) = motor {
+            joint = joint.with_motor(speed, WINDMILL_TORQUE);
+        }
+        spokes.push(world.add_joint(joint));
+        rim.push(ball);
+    }
+
+    for i in 0..rim.len() {
+        let (a, b) = (rim[i], rim[(i + 1) % rim.len()]);
+        let length = world.ball(a).unwrap().center.distance_to(world.ball(b).unwrap().center);
+        world.add_joint(Joint::new(a, b, length, JointKind::Distance));
+    }
+
+    spokes
+}
+
+// two loads hanging from static wheels, sharing one rope of `2 * drop`
+pub fn pulley(world: &mut World, center: Vector2, spread: f32, drop: f32) -> usize {
+    let mut rope = |offset: f32, load: f32| {
+        let anchor = world.add_ball(Ball::new(center + Vector2::new(offset, 0.0), 10.0, MACHINE_COLOR).with_static());
+
+        let ball = world.spawn(center + Vector2::new(offset, -drop), load, MACHINE_COLOR);
+        // the ropes themselves never bind on their own, the coupling does
+        world.add_joint(Joint::new(anchor, ball, drop * 2.0, JointKind::Rope))
+    };
+
+    let left = rope(-spread / 2.0, 12.0);
+    let right = rope(spread / 2.0, 18.0);
+
+    world.add_coupling(Coupling::pulley(left, right, 1.0, drop * 2.0))
+}
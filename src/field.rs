@@ -0,0 +1,49 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::snapshot::{RectangleDef, Vector2Def};
+use crate::Camera;
+
+const ARROW_SPACING: f32 = 60.0;
+const ARROW_COLOR: Color = Color::new(80, 160, 255, 120);
+
+// constant acceleration applied to every ball whose center is inside `area`
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ForceField {
+    #[serde(with = "RectangleDef")]
+    pub area: Rectangle,
+    #[serde(with = "Vector2Def")]
+    pub acceleration: Vector2,
+}
+
+impl ForceField {
+    pub fn new(area: Rectangle, acceleration: Vector2) -> Self {
+        Self { area, acceleration }
+    }
+
+    pub fn contains(&self, point: Vector2) -> bool {
+        point.x >= self.area.x && point.x <= self.area.x + self.area.width
+            && point.y >= self.area.y && point.y <= self.area.y + self.area.height
+    }
+
+    pub fn draw(&self, cam: &Camera, d: &mut RaylibDrawHandle) {
+        let direction = self.acceleration.normalized() * ARROW_SPACING * 0.4;
+        let side = Vector2::new(-direction.y, direction.x) * 0.3;
+
+        let mut y = self.area.y + ARROW_SPACING / 2.0;
+        while y < self.area.y + self.area.height {
+            let mut x = self.area.x + ARROW_SPACING / 2.0;
+            while x < self.area.x + self.area.width {
+                let tail = Vector2::new(x, y) - direction / 2.0;
+                let head = tail + direction;
+
+                d.draw_line_v(cam.project(tail), cam.project(head), ARROW_COLOR);
+                d.draw_line_v(cam.project(head), cam.project(head - direction * 0.3 + side), ARROW_COLOR);
+                d.draw_line_v(cam.project(head), cam.project(head - direction * 0.3 - side), ARROW_COLOR);
+                x += ARROW_SPACING;
+            }
+            y += ARROW_SPACING;
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use raylib::prelude::*;
+
+use balls::render::Renderer;
+
+use crate::hud::Hud;
+use crate::locale::Locale;
+
+// one column per frame, about two seconds at 120 fps
+const SAMPLES: usize = 240;
+const SIZE: Vector2 = Vector2::new(240.0, 60.0);
+const MARGIN: f32 = 10.0;
+// between the lines of the labels above the graph, in pixels
+const LINE_GAP: i32 = 4;
+// the graph's full height in milliseconds, longer frames are clipped and marked
+const CEILING: f32 = 50.0;
+const BUDGETS: [f32; 2] = [1000.0 / 60.0, 1000.0 / 30.0];
+const FRAME_COLOR: Color = Color::new(130, 130, 130, 255);
+const STEP_COLOR: Color = Color::new(0, 160, 255, 255);
+const CLIPPED_COLOR: Color = Color::RED;
+const BACKGROUND: Color = Color::new(0, 0, 0, 120);
+
+// a rolling profiler graph in the bottom right corner: frame times in gray with the physics step's share
+// of each in blue, against lines at the 60 and 30 fps budgets
+#[derive(Default)]
+pub struct FrameGraph {
+    pub enabled: bool,
+    // milliseconds, frame then step
+    samples: VecDeque<(f32, f32)>,
+}
+
+impl FrameGraph {
+    pub fn record(&mut self, frame: f32, step: Duration) {
+        if self.samples.len() == SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((frame * 1000.0, step.as_secs_f32() * 1000.0));
+    }
+
+    pub fn draw(&self, locale: &Locale, hud: &Hud, d: &mut RaylibDrawHandle) {
+        if !self.enabled {
+            return;
+        }
+
+        let origin = Vector2::new(hud.screen.x - SIZE.x - MARGIN, hud.screen.y - MARGIN);
+        let height = |ms: f32| ms.min(CEILING) / CEILING * SIZE.y;
+        d.draw_rectangle_v(origin - Vector2::new(0.0, SIZE.y), SIZE, BACKGROUND);
+
+        let width = SIZE.x / SAMPLES as f32;
+        for (i, &(frame, step)) in self.samples.iter().enumerate() {
+            let x = origin.x + (i + SAMPLES - self.samples.len()) as f32 * width + width / 2.0;
+            let bottom = Vector2::new(x, origin.y);
+            d.line(bottom, bottom - Vector2::new(0.0, height(frame)), width, FRAME_COLOR);
+            d.line(bottom, bottom - Vector2::new(0.0, height(step)), width, STEP_COLOR);
+            if frame > CEILING {
+                let top = bottom - Vector2::new(0.0, SIZE.y);
+                d.line(top, top + Vector2::new(0.0, 3.0), width, CLIPPED_COLOR);
+            }
+        }
+
+        for budget in BUDGETS {
+            let y = origin.y - height(budget);
+            d.line(Vector2::new(origin.x, y), Vector2::new(origin.x + SIZE.x, y), 1.0, Color::LIGHTGRAY);
+        }
+
+        let last = self.samples.back().copied().unwrap_or_default();
+        let worst = self.samples.iter().fold((0.0f32, 0.0f32), |(f, s), &(frame, step)| (f.max(frame), s.max(step)));
+        let lines = [
+            (locale.format("graph.frame", &[&format!("{:.1}", last.0), &format!("{:.1}", worst.0)]), FRAME_COLOR),
+            (locale.format("graph.step", &[&format!("{:.2}", last.1), &format!("{:.2}", worst.1)]), STEP_COLOR),
+        ];
+        // the labels sit just above the graph, in screen space like it, at the hud's text size
+        let line = hud.size() + LINE_GAP;
+        let top = (origin.y - SIZE.y) as i32 - lines.len() as i32 * line;
+        for (i, (text, color)) in lines.iter().enumerate() {
+            d.draw_text(text, origin.x as i32, top + line * i as i32, hud.size(), *color);
+        }
+    }
+}
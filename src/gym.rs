@@ -0,0 +1,228 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::observation;
+use crate::world::World;
+
+// launch speed of a full strength flick, in px/s
+const MAX_SPEED: f32 = 1500.0;
+// world steps simulated per action
+const FRAMES: usize = 30;
+const MAX_STEPS: usize = 50;
+const GOAL_RADIUS: f32 = 40.0;
+// what an action that loses the cue earns
+const LOST_REWARD: f32 = -1.0;
+// the step the app takes at its default frame cap
+const DT: f32 = 1.0 / 120.0;
+
+// flicks the cue ball towards `angle` (radians, counterclockwise from +x) at `strength` times the top speed;
+// a strength of zero lets the world run on without touching it
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Action {
+    pub angle: f32,
+    pub strength: f32,
+}
+
+impl Action {
+    pub fn new(angle: f32, strength: f32) -> Self {
+        Self { angle, strength }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct Observation {
+    // x, y, vx, vy, radius of every ball in id order
+    pub balls: Vec<[f32; 5]>,
+    // index of the cue ball in `balls`
+    pub cue: usize,
+    // x, y, radius
+    pub goal: [f32; 3],
+    // fixed size encodings, when the config asks for them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nearest: Vec<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub occupancy: Vec<f32>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct Transition {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+}
+
+#[non_exhaustive]
+pub struct Config {
+    // id of the ball the agent flicks
+    pub cue: usize,
+    pub goal: (Vector2, f32),
+    pub frames: usize,
+    pub max_steps: usize,
+    pub dt: f32,
+    // how many of the balls closest to the cue to encode, and the columns and rows of the occupancy grid
+    pub nearest: usize,
+    pub occupancy: Option<(usize, usize)>,
+}
+
+impl Config {
+    // the first moving ball is the cue, the goal sits in the top right corner
+    pub fn new(world: &World) -> Self {
+        let cue = world.balls.iter().find(|b| !b.is_static).map_or(0, |b| b.id);
+        let bounds = world.bounds;
+        let goal = Vector2::new(bounds.x + bounds.width - GOAL_RADIUS * 1.5, bounds.y + bounds.height - GOAL_RADIUS * 1.5);
+        Self { cue, goal: (goal, GOAL_RADIUS), frames: FRAMES, max_steps: MAX_STEPS, dt: DT, nearest: 0, occupancy: None }
+    }
+}
+
+// an episode is getting the cue ball's center into the goal within `max_steps` actions; every action earns
+// how much closer the cue got, as a fraction of the world's diagonal, and reaching the goal earns 1 more;
+// losing the cue ends the episode with `LOST_REWARD`
+pub struct Env {
+    pub config: Config,
+    pub world: World,
+    start: World,
+    steps: usize,
+}
+
+impl Env {
+    pub fn new(world: World, config: Config) -> Self {
+        Self { config, world: world.fork(), start: world, steps: 0 }
+    }
+
+    pub fn reset(&mut self) -> Observation {
+        self.world = self.start.fork();
+        self.steps = 0;
+        self.observe()
+    }
+
+    pub fn step(&mut self, action: Action) -> Transition {
+        let before = self.distance();
+        if let Some(cue) = self.world.ball_mut(self.config.cue) {
+            let speed = action.strength.clamp(0.0, 1.0) * MAX_SPEED;
+            cue.apply_impulse(Vector2::new(action.angle.cos(), action.angle.sin()) * speed * cue.mass);
+        }
+
+        for _ in 0..self.config.frames {
+            self.world.step(self.config.dt);
+            self.world.events.clear();
+            if self.scored() {
+                break;
+            }
+        }
+        self.steps += 1;
+
+        let bounds = self.world.bounds;
+        let diagonal = (bounds.width * bounds.width + bounds.height * bounds.height).sqrt();
+        let scored = self.scored();
+        let reward = match (before, self.distance()) {
+            (Some(before), Some(after)) => (before - after) / diagonal + if scored { 1.0 } else { 0.0 },
+            // the cue was despawned or removed, which is no progress at all
+            _ => LOST_REWARD,
+        };
+        let lost = self.world.ball(self.config.cue).is_none();
+
+        Transition { observation: self.observe(), reward, done: scored || lost || self.steps >= self.config.max_steps }
+    }
+
+    pub fn scored(&self) -> bool {
+        let (goal, radius) = self.config.goal;
+        self.world.ball(self.config.cue).is_some_and(|b| b.center.distance_to(goal) <= radius)
+    }
+
+    // how far the cue is from the goal's edge, zero once inside, or None once the cue is gone
+    fn distance(&self) -> Option<f32> {
+        let (goal, radius) = self.config.goal;
+        self.world.ball(self.config.cue).map(|b| (b.center.distance_to(goal) - radius).max(0.0))
+    }
+
+    pub fn observe(&self) -> Observation {
+        let mut balls: Vec<_> = self.world.balls.iter().collect();
+        balls.sort_by_key(|b| b.id);
+        let (goal, radius) = self.config.goal;
+        let cue = self.world.ball(self.config.cue);
+
+        Observation {
+            cue: balls.iter().position(|b| b.id == self.config.cue).unwrap_or(0),
+            balls: balls.iter().map(|b| [b.center.x, b.center.y, b.velocity.x, b.velocity.y, b.radius]).collect(),
+            goal: [goal.x, goal.y, radius],
+            nearest: match cue {
+                Some(cue) if self.config.nearest > 0 => observation::nearest(&self.world, cue.center, self.config.nearest, Some(cue.id)),
+                _ => vec![0.0; self.config.nearest * observation::BALL_FEATURES],
+            },
+            occupancy: self.config.occupancy.map_or(Vec::new(), |(columns, rows)| observation::occupancy(&self.world, columns, rows)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ball;
+
+    fn env() -> Env {
+        let mut world = World::new();
+        world.gravity = Vector2::zero();
+        world.add_ball(Ball::new(Vector2::new(400.0, 300.0), 10.0, Color::RED));
+        let config = Config::new(&world);
+        Env::new(world, config)
+    }
+
+    #[test]
+    fn flicking_towards_the_goal_scores() {
+        let mut env = env();
+        let (goal, _) = env.config.goal;
+        let aim = goal - Vector2::new(400.0, 300.0);
+        let transition = env.step(Action { angle: aim.y.atan2(aim.x), strength: 1.0 });
+        assert!(transition.done);
+        assert!(transition.reward > 1.0);
+    }
+
+    #[test]
+    fn flicking_away_from_the_goal_costs() {
+        let mut env = env();
+        let transition = env.step(Action { angle: std::f32::consts::PI * 1.25, strength: 0.2 });
+        assert!(!transition.done);
+        assert!(transition.reward < 0.0);
+    }
+
+    #[test]
+    fn losing_the_cue_is_not_progress() {
+        let mut env = env();
+        let cue = env.config.cue;
+        env.world.remove_balls(&[cue]);
+        let transition = env.step(Action { angle: 0.0, strength: 1.0 });
+        assert!(transition.done);
+        assert_eq!(transition.reward, LOST_REWARD);
+    }
+
+    #[test]
+    fn an_episode_runs_from_reset_until_the_cue_is_in_the_goal() {
+        let mut env = env();
+        let start = env.reset();
+        let cue = start.balls[start.cue];
+        let (goal, _) = env.config.goal;
+        let aim = goal - Vector2::new(cue[0], cue[1]);
+        let angle = aim.y.atan2(aim.x);
+
+        // a nudge away moves the cue without ending the episode, and resetting puts it back
+        let nudged = env.step(Action::new(angle + std::f32::consts::PI, 0.1));
+        assert!(!nudged.done);
+        assert_ne!(nudged.observation.balls[nudged.observation.cue], cue);
+        assert_eq!(env.reset().balls, start.balls);
+
+        let mut transition = env.step(Action::new(angle, 0.3));
+        let mut total = transition.reward;
+        while !transition.done {
+            transition = env.step(Action::new(0.0, 0.0));
+            total += transition.reward;
+        }
+        assert!(env.scored());
+        assert!(total > 1.0);
+        let [x, y, radius] = transition.observation.goal;
+        let cue = transition.observation.balls[transition.observation.cue];
+        assert!(Vector2::new(cue[0], cue[1]).distance_to(Vector2::new(x, y)) <= radius);
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::{self, BufRead, Write};
+
+use raylib::prelude::*;
+use serde::Deserialize;
+
+use balls::gym::{Action, Config, Env};
+use balls::scene::Scene;
+
+use crate::{arg, headless};
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Request {
+    Reset,
+    Step(Action),
+}
+
+// `gym [--preset <name> | --scene <path>] [--cue <id>] [--goal <x,y,r>] [--frames <n>] [--max-steps <n>]` serves
+// an environment over stdin and stdout, one JSON message per line: `{"type":"reset"}` answers with an observation,
+// `{"type":"step","angle":a,"strength":s}` with a transition; `--nearest <k>` and `--occupancy <columns>x<rows>`
+// add fixed size encodings to every observation
+pub fn run(args: &[String]) {
+    let world = match arg(args, "--scene") {
+        Some(path) => match Scene::load(path) {
+            Ok(scene) => scene.world,
+            Err(err) => {
+                eprintln!("could not load {}: {}", path, err);
+                return;
+            }
+        },
+        None => match headless::preset(args) {
+            Some(preset) => (preset.build)(),
+            None => return,
+        },
+    };
+
+    let mut config = Config::new(&world);
+    if let Some(cue) = arg(args, "--cue").and_then(|v| v.parse().ok()) {
+        config.cue = cue;
+    }
+    if let Some(goal) = arg(args, "--goal") {
+        match goal.split(',').map(|v| v.trim().parse::<f32>()).collect::<Result<Vec<_>, _>>().as_deref() {
+            Ok(&[x, y, r]) => config.goal = (Vector2::new(x, y), r),
+            _ => {
+                eprintln!("expected --goal <x,y,r>, got {:?}", goal);
+                return;
+            }
+        }
+    }
+    if let Some(frames) = arg(args, "--frames").and_then(|v| v.parse().ok()) {
+        config.frames = frames;
+    }
+    if let Some(max_steps) = arg(args, "--max-steps").and_then(|v| v.parse().ok()) {
+        config.max_steps = max_steps;
+    }
+    if let Some(k) = arg(args, "--nearest").and_then(|v| v.parse().ok()) {
+        config.nearest = k;
+    }
+    if let Some(grid) = arg(args, "--occupancy") {
+        match grid.split_once('x').and_then(|(c, r)| Some((c.parse().ok()?, r.parse().ok()?))) {
+            Some(size) => config.occupancy = Some(size),
+            None => {
+                eprintln!("expected --occupancy <columns>x<rows>, got {:?}", grid);
+                return;
+            }
+        }
+    }
+    if world.ball(config.cue).is_none() {
+        eprintln!("there is no ball #{} to use as the cue", config.cue);
+        return;
+    }
+
+    let mut env = Env::new(world, config);
+    let mut out = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Reset) => serde_json::to_string(&env.reset()),
+            Ok(Request::Step(action)) => serde_json::to_string(&env.step(action)),
+            Err(err) => Ok(serde_json::json!({ "error": err.to_string() }).to_string()),
+        };
+        if writeln!(out, "{}", reply.unwrap()).and_then(|_| out.flush()).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use balls::settle::{self, Settle};
+
+use crate::locale::{self, Locale};
+use crate::metrics::{self, Metrics};
+use crate::presets::{Preset, PRESETS};
+use crate::stats::Stats;
+use crate::{arg, write_stats, Clock, FPS_CAP};
+
+// runs a preset without a window:
+// `--preset <name>` picks the world, `--steps <n>` stops after n steps instead of running forever,
+// `--fps <n>` paces the loop (0 runs flat out), and `--metrics <address>` serves prometheus metrics;
+// `--until-settled` stops once the world comes to rest and prints the settle report as JSON, at rest meaning
+// the total kinetic energy stays under `--settle-energy <mass px^2/s^2>` for `--settle-steps <n>`
+pub fn run(args: &[String]) {
+    let Some(preset) = preset(args) else {
+        return;
+    };
+    let steps = arg(args, "--steps").and_then(|v| v.parse::<u64>().ok());
+    let fps = arg(args, "--fps").and_then(|v| v.parse::<f32>().ok()).unwrap_or(FPS_CAP);
+
+    let metrics = Arc::new(Mutex::new(Metrics::default()));
+    if let Some(address) = arg(args, "--metrics") {
+        if let Err(err) = metrics::serve(address, metrics.clone()) {
+            eprintln!("could not serve metrics on {}: {}", address, err);
+            return;
+        }
+        println!("serving metrics on http://{}/metrics", address);
+    }
+
+    // the step is fixed so a run does not depend on how fast the machine is
+    let dt = 1.0 / if fps > 0.0 { fps } else { FPS_CAP };
+    let mut clock = Clock::new(Clock::frame_cap(fps));
+    // stats keep every step time, so long runs only pay for them when asked
+    let stats_path = arg(args, "--stats");
+    let mut stats = stats_path.map(|_| Stats::default());
+    let mut world = (preset.build)();
+    let until_settled = args.iter().any(|a| a == "--until-settled");
+    let mut settle = Settle::new(
+        arg(args, "--settle-energy").and_then(|v| v.parse::<f32>().ok()).unwrap_or(settle::ENERGY),
+        arg(args, "--settle-steps").and_then(|v| v.parse::<usize>().ok()).unwrap_or(settle::STEPS),
+    );
+
+    let mut step = 0;
+    while steps.is_none_or(|steps| step < steps) {
+        clock.tick();
+
+        let step_start = Instant::now();
+        world.step(dt);
+        let elapsed = step_start.elapsed();
+
+        world.events.clear();
+        if let Some(stats) = &mut stats {
+            stats.record(&world, elapsed);
+        }
+        metrics.lock().unwrap().record(&world, elapsed);
+        step += 1;
+
+        if let Some(report) = settle.record(&world, dt) {
+            if until_settled {
+                println!("{}", serde_json::to_string(&report).unwrap());
+                break;
+            }
+        }
+    }
+
+    if until_settled && settle.report().is_none() {
+        println!("did not settle within {} steps", step);
+    }
+
+    if let (Some(stats), Some(path)) = (&stats, stats_path) {
+        println!("{}", write_stats(stats, path, &Locale::load(locale::ENGLISH)));
+    }
+}
+
+// the preset named by `--preset`, the first one without it; None after reporting an unknown name
+pub fn preset(args: &[String]) -> Option<&'static Preset> {
+    let Some(name) = arg(args, "--preset") else {
+        return Some(&PRESETS[0]);
+    };
+
+    let found = PRESETS.iter().find(|p| p.name == name);
+    if found.is_none() {
+        let names: Vec<&str> = PRESETS.iter().map(|p| p.name).collect();
+        eprintln!("unknown preset {:?}, expected one of {:?}", name, names);
+    }
+    found
+}
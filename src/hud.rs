@@ -0,0 +1,37 @@
+use raylib::prelude::*;
+
+// the layout the hud was placed for, and how much larger it gets in accessible mode
+const DESIGN: Vector2 = Vector2::new(640.0, 480.0);
+const TEXT_SIZE: i32 = 10;
+const LARGE_TEXT: f32 = 1.6;
+
+// draws hud text laid out for a 640x480 window, scaled up in accessible mode; text in the right or bottom half
+// keeps its distance from that edge, so larger text grows into the window rather than off it
+#[derive(Copy, Clone)]
+pub struct Hud {
+    pub scale: f32,
+    pub screen: Vector2,
+}
+
+impl Hud {
+    pub fn new(accessible: bool, screen: Vector2) -> Self {
+        Self { scale: if accessible { LARGE_TEXT } else { 1.0 }, screen }
+    }
+
+    pub fn size(&self) -> i32 {
+        (TEXT_SIZE as f32 * self.scale).round() as i32
+    }
+
+    pub fn place(&self, x: i32, y: i32) -> (i32, i32) {
+        let along = |v: i32, design: f32, screen: f32| {
+            let v = v as f32;
+            if v > design / 2.0 { screen - (design - v) * self.scale } else { v * self.scale }
+        };
+        (along(x, DESIGN.x, self.screen.x) as i32, along(y, DESIGN.y, self.screen.y) as i32)
+    }
+
+    pub fn text(&self, d: &mut RaylibDrawHandle, text: &str, x: i32, y: i32, color: Color) {
+        let (x, y) = self.place(x, y);
+        d.draw_text(text, x, y, self.size(), color);
+    }
+}
@@ -0,0 +1,102 @@
+use std::fs;
+use std::io;
+use std::time::Instant;
+
+use raylib::core::input::key_from_i32;
+use raylib::prelude::*;
+use serde::Serialize;
+
+use balls::world::World;
+
+use crate::settings::LAST_KEY;
+
+const BUTTONS: [MouseButton; 3] = [MouseButton::MOUSE_LEFT_BUTTON, MouseButton::MOUSE_RIGHT_BUTTON, MouseButton::MOUSE_MIDDLE_BUTTON];
+
+// keys and buttons carry raylib's codes
+#[derive(Copy, Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Input {
+    KeyDown { key: i32 },
+    KeyUp { key: i32 },
+    ButtonDown { button: i32 },
+    ButtonUp { button: i32 },
+    // in screen pixels
+    MouseMove { x: f32, y: f32 },
+    Wheel { amount: f32 },
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct InputEvent {
+    pub frame: u64,
+    // wall clock seconds since recording started, taken when the frame polled its input
+    pub time: f32,
+    // simulated seconds stepped before the frame, which bullet time stretches
+    pub sim_time: f32,
+    #[serde(flatten)]
+    pub input: Input,
+}
+
+// raw input as the window saw it, kept apart from any physics state so it can be lined up against frame
+// times or fed to a rerun of `start` with other solver settings
+#[derive(Serialize)]
+pub struct InputLog {
+    pub start: World,
+    pub events: Vec<InputEvent>,
+    #[serde(skip)]
+    clock: Instant,
+    #[serde(skip)]
+    frame: u64,
+    #[serde(skip)]
+    sim_time: f32,
+    #[serde(skip)]
+    mouse: Option<Vector2>,
+}
+
+impl InputLog {
+    pub fn new(start: &World) -> Self {
+        Self { start: start.fork(), events: Vec::new(), clock: Instant::now(), frame: 0, sim_time: 0.0, mouse: None }
+    }
+
+    // call once a frame before anything reads the input; it only peeks, so later reads see the same state
+    pub fn poll(&mut self, rl: &RaylibHandle) {
+        let mut inputs = Vec::new();
+        for key in (0..=LAST_KEY).filter_map(key_from_i32) {
+            if rl.is_key_pressed(key) {
+                inputs.push(Input::KeyDown { key: key as i32 });
+            }
+            if rl.is_key_released(key) {
+                inputs.push(Input::KeyUp { key: key as i32 });
+            }
+        }
+        for button in BUTTONS {
+            if rl.is_mouse_button_pressed(button) {
+                inputs.push(Input::ButtonDown { button: button as i32 });
+            }
+            if rl.is_mouse_button_released(button) {
+                inputs.push(Input::ButtonUp { button: button as i32 });
+            }
+        }
+        let mouse = rl.get_mouse_position();
+        if self.mouse != Some(mouse) {
+            self.mouse = Some(mouse);
+            inputs.push(Input::MouseMove { x: mouse.x, y: mouse.y });
+        }
+        let wheel = rl.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            inputs.push(Input::Wheel { amount: wheel });
+        }
+
+        let time = self.clock.elapsed().as_secs_f32();
+        self.events.extend(inputs.into_iter().map(|input| InputEvent { frame: self.frame, time, sim_time: self.sim_time, input }));
+    }
+
+    // the simulated time the frame stepped
+    pub fn advance(&mut self, dt: f32) {
+        self.frame += 1;
+        self.sim_time += dt;
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+    }
+}
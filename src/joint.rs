@@ -0,0 +1,346 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Renderer;
+use crate::{Ball, Camera};
+
+const JOINT_COLOR: Color = Color::DARKGRAY;
+const SPRING_COILS: usize = 12;
+const SPRING_WIDTH: f32 = 6.0;
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum JointKind {
+    Distance,
+    Spring { stiffness: f32, damping: f32 },
+    // only pulls once stretched past its length
+    Rope,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AngleLimit {
+    pub min: f32,
+    pub max: f32,
+}
+
+// drives the hinge towards `speed` (radians per second) with at most `max_torque`
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Motor {
+    pub speed: f32,
+    pub max_torque: f32,
+}
+
+// every joint is also a hinge around its `a` ball; the hinge angle is the direction towards `b`
+// measured from the parent joint's direction into `a` (or from the world x axis without a parent),
+// offset by `reference`
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Joint {
+    pub id: usize,
+    pub a: usize,
+    pub b: usize,
+    pub length: f32,
+    pub kind: JointKind,
+    pub parent: Option<usize>,
+    pub reference: f32,
+    pub limit: Option<AngleLimit>,
+    pub motor: Option<Motor>,
+    pub break_impulse: Option<f32>,
+}
+
+impl Joint {
+    pub fn new(a: usize, b: usize, length: f32, kind: JointKind) -> Self {
+        Self {
+            id: 0,
+            a,
+            b,
+            length,
+            kind,
+            parent: None,
+            reference: 0.0,
+            limit: None,
+            motor: None,
+            break_impulse: None,
+        }
+    }
+
+    pub fn with_parent(mut self, parent: usize) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn with_reference(mut self, reference: f32) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    pub fn with_limit(mut self, min: f32, max: f32) -> Self {
+        self.limit = Some(AngleLimit { min, max });
+        self
+    }
+
+    pub fn with_motor(mut self, speed: f32, max_torque: f32) -> Self {
+        self.motor = Some(Motor { speed, max_torque });
+        self
+    }
+
+    pub fn with_break_impulse(mut self, impulse: f32) -> Self {
+        self.break_impulse = Some(impulse);
+        self
+    }
+
+    pub fn is_hinge(&self) -> bool {
+        self.limit.is_some() || self.motor.is_some()
+    }
+
+    pub fn other(&self, id: usize) -> usize {
+        if self.a == id { self.b } else { self.a }
+    }
+
+    // returns the impulse magnitude the joint applied
+    pub fn solve(&self, a: &mut Ball, b: &mut Ball, dt: f32) -> f32 {
+        let inv_a = a.inverse_mass();
+        let inv_b = b.inverse_mass();
+        let inv_total = inv_a + inv_b;
+        if inv_total == 0.0 {
+            return 0.0;
+        }
+
+        let delta = b.center - a.center;
+        let distance = delta.length();
+        if distance <= f32::EPSILON {
+            return 0.0;
+        }
+
+        let normal = delta / distance;
+        let stretch = distance - self.length;
+        let closing = (b.velocity - a.velocity).dot(normal);
+
+        let impulse = match self.kind {
+            JointKind::Distance => {
+                a.center += normal * (stretch * inv_a / inv_total);
+                b.center -= normal * (stretch * inv_b / inv_total);
+                closing / inv_total
+            }
+            JointKind::Spring { stiffness, damping } => (stiffness * stretch + damping * closing) * dt,
+            JointKind::Rope => {
+                if stretch <= 0.0 {
+                    return 0.0;
+                }
+                a.center += normal * (stretch * inv_a / inv_total);
+                b.center -= normal * (stretch * inv_b / inv_total);
+                closing.max(0.0) / inv_total
+            }
+        };
+
+        a.velocity += normal * (impulse * inv_a);
+        b.velocity -= normal * (impulse * inv_b);
+
+        // a joint pulling on a sleeping ball has to wake it up
+        if impulse.abs() > f32::EPSILON {
+            a.freezing = a.freezing.max(10);
+            b.freezing = b.freezing.max(10);
+        }
+
+        impulse.abs()
+    }
+
+    pub fn draw(&self, a: &Ball, b: &Ball, cam: &Camera, color: Option<Color>, d: &mut impl Renderer) {
+        let start = cam.project(a.center);
+        let end = cam.project(b.center);
+        let color = color.unwrap_or(JOINT_COLOR);
+
+        match self.kind {
+            JointKind::Distance => d.line(start, end, 2.0, color),
+            JointKind::Rope => d.line(start, end, 1.0, color),
+            JointKind::Spring { .. } => {
+                let along = end - start;
+                let side = Vector2::new(-along.y, along.x).normalized() * SPRING_WIDTH;
+
+                let mut prev = start;
+                for i in 1..SPRING_COILS {
+                    let offset = if i % 2 == 0 { side } else { -side };
+                    let point = start + along * (i as f32 / SPRING_COILS as f32) + offset;
+                    d.line(prev, point, 1.0, color);
+                    prev = point;
+                }
+                d.line(prev, end, 1.0, color);
+            }
+        }
+    }
+
+    // moves the ends apart by `position` and pushes them apart by `impulse`, both along the joint
+    pub fn push(&self, a: &mut Ball, b: &mut Ball, position: f32, impulse: f32) {
+        let normal = (b.center - a.center).normalized();
+        let (inv_a, inv_b) = (a.inverse_mass(), b.inverse_mass());
+
+        a.center -= normal * (position * inv_a);
+        b.center += normal * (position * inv_b);
+        a.velocity -= normal * (impulse * inv_a);
+        b.velocity += normal * (impulse * inv_b);
+    }
+
+    pub fn distance_to(&self, a: &Ball, b: &Ball, point: Vector2) -> f32 {
+        let along = b.center - a.center;
+        let t = ((point - a.center).dot(along) / along.length_sqr().max(f32::EPSILON)).clamp(0.0, 1.0);
+
+        point.distance_to(a.center + along * t)
+    }
+}
+
+pub struct Hinge<'a> {
+    pub parent_end: Option<&'a mut Ball>,
+    pub pivot: &'a Ball,
+    pub child_end: &'a mut Ball,
+    pub reference: f32,
+}
+
+impl Hinge<'_> {
+    fn parent_r(&self) -> Option<Vector2> {
+        self.parent_end.as_ref().map(|p| p.center - self.pivot.center)
+    }
+
+    fn child_r(&self) -> Vector2 {
+        self.child_end.center - self.pivot.center
+    }
+
+    pub fn angle(&self) -> f32 {
+        let base = self.parent_r().map_or(0.0, |r| angle_of(-r));
+        wrap_angle(angle_of(self.child_r()) - base - self.reference)
+    }
+
+    pub fn spin(&self) -> f32 {
+        let child = spin_of(self.child_r(), self.child_end.velocity - self.pivot.velocity);
+        let parent = self.parent_end.as_ref()
+            .map_or(0.0, |p| spin_of(p.center - self.pivot.center, p.velocity - self.pivot.velocity));
+
+        child - parent
+    }
+
+    pub fn inverse_inertia(&self) -> f32 {
+        let (child, parent) = self.weights();
+        child + parent
+    }
+
+    // angular inverse masses of the child and parent ends
+    fn weights(&self) -> (f32, f32) {
+        let child = self.child_end.inverse_mass() / self.child_r().length_sqr().max(f32::EPSILON);
+        let parent = self.parent_end.as_ref().map_or(0.0, |p| {
+            p.inverse_mass() / (p.center - self.pivot.center).length_sqr().max(f32::EPSILON)
+        });
+
+        (child, parent)
+    }
+
+    // bends the hinge by `angle`, split between both ends
+    fn bend(&mut self, angle: f32) {
+        let (w_child, w_parent) = self.weights();
+        let w_total = w_child + w_parent;
+        if w_total == 0.0 {
+            return;
+        }
+
+        let pivot = self.pivot.center;
+        let child_r = self.child_r();
+        self.child_end.center = pivot + rotate(child_r, angle * w_child / w_total);
+
+        if let Some(parent) = self.parent_end.as_mut() {
+            parent.center = pivot + rotate(parent.center - pivot, -angle * w_parent / w_total);
+        }
+    }
+
+    // changes the relative spin by `delta`, returning the angular impulse that took
+    pub fn add_spin(&mut self, delta: f32) -> f32 {
+        let (w_child, w_parent) = self.weights();
+        let w_total = w_child + w_parent;
+        if w_total == 0.0 {
+            return 0.0;
+        }
+
+        let pivot = self.pivot.center;
+        let child_r = self.child_r();
+        self.child_end.velocity += perpendicular(child_r) * (delta * w_child / w_total);
+        self.child_end.freezing = self.child_end.freezing.max(10);
+
+        if let Some(parent) = self.parent_end.as_mut() {
+            parent.velocity += perpendicular(parent.center - pivot) * (-delta * w_parent / w_total);
+            parent.freezing = parent.freezing.max(10);
+        }
+
+        delta / w_total
+    }
+}
+
+impl AngleLimit {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    pub fn solve(&self, hinge: &mut Hinge) {
+        let angle = hinge.angle();
+        let error = if angle > self.max {
+            angle - self.max
+        } else if angle < self.min {
+            angle - self.min
+        } else {
+            return;
+        };
+
+        hinge.bend(-error);
+
+        // cancel the relative spin that would drive the bend further past the limit
+        let spin = hinge.spin();
+        if spin * error > 0.0 {
+            hinge.add_spin(-spin);
+        }
+    }
+}
+
+impl Motor {
+    pub fn new(speed: f32, max_torque: f32) -> Self {
+        Self { speed, max_torque }
+    }
+
+    pub fn solve(&self, hinge: &mut Hinge, dt: f32) {
+        let (w_child, w_parent) = hinge.weights();
+        let w_total = w_child + w_parent;
+        if w_total == 0.0 {
+            return;
+        }
+
+        // the torque cap bounds how much of the spin difference can be made up this step
+        let max_delta = self.max_torque * dt * w_total;
+        let delta = (self.speed - hinge.spin()).clamp(-max_delta, max_delta);
+
+        hinge.add_spin(delta);
+    }
+}
+
+pub(crate) fn angle_of(v: Vector2) -> f32 {
+    v.y.atan2(v.x)
+}
+
+pub(crate) fn wrap_angle(angle: f32) -> f32 {
+    let turn = std::f32::consts::TAU;
+    angle - turn * ((angle + turn / 2.0) / turn).floor()
+}
+
+// `v` turned counterclockwise by `angle` radians
+pub fn rotate(v: Vector2, angle: f32) -> Vector2 {
+    let (sin, cos) = angle.sin_cos();
+    Vector2::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
+
+fn spin_of(r: Vector2, relative_velocity: Vector2) -> f32 {
+    cross(r, relative_velocity) / r.length_sqr().max(f32::EPSILON)
+}
+
+pub(crate) fn perpendicular(v: Vector2) -> Vector2 {
+    Vector2::new(-v.y, v.x)
+}
+
+pub(crate) fn cross(a: Vector2, b: Vector2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
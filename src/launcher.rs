@@ -0,0 +1,111 @@
+use rand::random;
+use raylib::prelude::*;
+
+use balls::joint::rotate;
+use balls::render::Renderer;
+use balls::world::World;
+use balls::{Ball, Camera};
+
+use crate::overlay::Prediction;
+
+// a drag this long on screen launches at full power
+const FULL_DRAG: f32 = 200.0;
+const MAX_SPEED: f32 = 2000.0;
+const MAX_COUNT: usize = 20;
+const MAX_SPREAD: f32 = std::f32::consts::PI;
+const PREVIEW_STEPS: usize = 60;
+const PREVIEW_DT: f32 = 1.0 / 60.0;
+const AIM_COLOR: Color = Color::new(0, 160, 255, 255);
+const GAUGE_SIZE: Vector2 = Vector2::new(8.0, 60.0);
+
+// press to set where balls come from, drag towards where they should go and release to fire; the drag length
+// is the power, and a volley of `count` balls fans out over `spread` radians side by side
+pub struct Launcher {
+    pub count: usize,
+    pub spread: f32,
+    pub radius: f32,
+    origin: Option<Vector2>,
+    prediction: Prediction,
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self { count: 1, spread: 0.0, radius: 10.0, origin: None, prediction: Prediction::default() }
+    }
+}
+
+impl Launcher {
+    pub fn begin(&mut self, screen: Vector2) {
+        self.origin = Some(screen);
+    }
+
+    pub fn cancel(&mut self) {
+        self.origin = None;
+        self.prediction = Prediction::default();
+    }
+
+    pub fn resize_volley(&mut self, count: isize, spread: f32) {
+        self.count = self.count.saturating_add_signed(count).clamp(1, MAX_COUNT);
+        self.spread = (self.spread + spread).clamp(0.0, MAX_SPREAD);
+    }
+
+    // from 0 to 1
+    pub fn power(&self, mouse: Vector2) -> f32 {
+        self.origin.map_or(0.0, |origin| (origin.distance_to(mouse) / FULL_DRAG).min(1.0))
+    }
+
+    // the balls a release at `mouse` would fire, not yet in the world
+    fn volley(&self, mouse: Vector2, cam: &Camera) -> Vec<Ball> {
+        let Some(origin) = self.origin else {
+            return Vec::new();
+        };
+        let (from, to) = (cam.unproject(origin), cam.unproject(mouse));
+        if from == to {
+            return Vec::new();
+        }
+
+        let aim = (to - from).normalized();
+        let side = Vector2::new(-aim.y, aim.x);
+        let speed = self.power(mouse) * MAX_SPEED;
+        (0..self.count)
+            .map(|i| {
+                let offset = i as f32 - (self.count - 1) as f32 / 2.0;
+                let turn = if self.count > 1 { self.spread * offset / (self.count - 1) as f32 } else { 0.0 };
+                let direction = rotate(aim, turn);
+                let center = from + side * offset * self.radius * 2.2;
+                Ball::new(center, self.radius, Color::new(random(), random(), random(), 255)).with_velocity(direction * speed)
+            })
+            .collect()
+    }
+
+    // fires the volley and returns the new balls' ids
+    pub fn release(&mut self, world: &mut World, mouse: Vector2, cam: &Camera) -> Vec<usize> {
+        let volley = self.volley(mouse, cam);
+        self.origin = None;
+        volley.into_iter().map(|ball| world.add_ball(ball)).collect()
+    }
+
+    // the aim, the gauge and where the volley would go, worked out on a fork of the world
+    pub fn draw(&mut self, world: &World, cam: &Camera, mouse: Vector2, d: &mut impl Renderer) {
+        let Some(origin) = self.origin else {
+            return;
+        };
+        d.line(origin, mouse, 1.0, AIM_COLOR);
+
+        let volley = self.volley(mouse, cam);
+        for path in self.prediction.paths(world, &[], &volley, PREVIEW_STEPS, PREVIEW_DT) {
+            d.circle(cam.project(path[0]), cam.scale(self.radius), AIM_COLOR.fade(0.5));
+            for pair in path.windows(2) {
+                d.line(cam.project(pair[0]), cam.project(pair[1]), 1.0, AIM_COLOR.fade(0.5));
+            }
+        }
+
+        // fills from the bottom, green to red
+        let power = self.power(mouse);
+        let bottom = origin + Vector2::new(-GAUGE_SIZE.x - 16.0, GAUGE_SIZE.y / 2.0);
+        let top = bottom - Vector2::new(0.0, GAUGE_SIZE.y);
+        let fill = Color::new((255.0 * power) as u8, (255.0 * (1.0 - power)) as u8, 0, 255);
+        d.line(bottom, top, GAUGE_SIZE.x + 2.0, Color::DARKGRAY);
+        d.line(bottom, bottom.lerp(top, power), GAUGE_SIZE.x, fill);
+    }
+}
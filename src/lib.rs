@@ -0,0 +1,408 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use accessibility::Pattern;
+use bounce::{BounceLimit, BounceRule};
+use magnet::Magnet;
+use material::Material;
+use pulse::Pulse;
+use render::Renderer;
+use snapshot::{ColorDef, Vector2Def};
+
+mod accessibility;
+pub mod bounce;
+mod broadphase;
+pub mod camera_path;
+pub mod coupling;
+pub mod event;
+pub mod export;
+pub mod factory;
+pub mod field;
+pub mod gym;
+pub mod joint;
+pub mod magnet;
+pub mod material;
+pub mod network;
+pub mod observation;
+pub mod prelude;
+pub mod probe;
+pub mod pulse;
+pub mod render;
+pub mod scene;
+pub mod settle;
+pub mod snapshot;
+pub mod terrain;
+pub mod thermal;
+pub mod thruster;
+pub mod time_zone;
+pub mod world;
+
+#[derive(Copy, Clone)]
+pub struct Camera {
+    pub position: Vector2,
+
+    scale: f32,
+    scale_v: Vector2,
+}
+
+impl Camera {
+    pub fn new(position: Vector2, scale: f32) -> Self {
+        Self {
+            position,
+            scale,
+            scale_v: Vector2::one() * scale,
+        }
+    }
+
+    pub fn set_position(&mut self, position: Vector2) {
+        self.position = position;
+    }
+
+    // keeps the axes flipped the way they were
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.scale_v = Vector2::new(scale * self.scale_v.x.signum(), scale * self.scale_v.y.signum());
+    }
+
+    // puts `center` in the middle of a `screen` sized view, magnified by `zoom`
+    pub fn look_at(&mut self, center: Vector2, zoom: f32, screen: Vector2) {
+        self.set_scale(zoom);
+        self.position = screen / 2.0 - center * self.scale_v;
+    }
+
+    pub fn invert_v(mut self) -> Self {
+        self.scale_v *= Vector2::new(1.0, -1.0);
+        self
+    }
+
+    pub fn invert_h(mut self) -> Self {
+        self.scale_v *= Vector2::new(-1.0, 1.0);
+        self
+    }
+
+    pub fn project(&self, v: Vector2) -> Vector2 {
+        (v * self.scale_v) + self.position
+    }
+
+    pub fn unproject(&self, v: Vector2) -> Vector2 {
+        (v - self.position) / self.scale_v
+    }
+
+    pub fn scale(&self, v: f32) -> f32 {
+        v * self.scale
+    }
+}
+
+#[derive(Default, Copy, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Ball {
+    pub id: usize,
+    #[serde(with = "Vector2Def")]
+    pub center: Vector2,
+    pub radius: f32,
+    pub mass: f32,
+    #[serde(with = "ColorDef")]
+    pub color: Color,
+    #[serde(with = "Vector2Def")]
+    pub velocity: Vector2,
+    pub freezing: i32,
+    pub material: Material,
+    pub is_static: bool,
+    // wall impacts so far, and the limit overriding the world's
+    #[serde(default)]
+    pub bounces: u32,
+    #[serde(default)]
+    pub bounce_limit: Option<BounceLimit>,
+    #[serde(default)]
+    pub magnet: Option<Magnet>,
+    #[serde(default)]
+    pub pulse: Option<Pulse>,
+    // how fast the rim moved outwards over the last step, from the pulse
+    #[serde(skip)]
+    pub growth: f32,
+}
+
+impl Ball {
+    // the id is assigned once the ball is added to a world
+    pub fn new(center: Vector2, radius: f32, color: Color) -> Ball {
+        Ball {
+            id: 0,
+            center,
+            radius,
+            color,
+            mass: Material::default().mass_for(radius),
+            velocity: Vector2::zero(),
+            freezing: 10,
+            material: Material::default(),
+            is_static: false,
+            bounces: 0,
+            bounce_limit: None,
+            magnet: None,
+            pulse: None,
+            growth: 0.0,
+        }
+    }
+
+    pub fn with_material(mut self, material: Material) -> Self {
+        self.set_material(material);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: Vector2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_static(mut self) -> Self {
+        self.set_static(true);
+        self
+    }
+
+    pub fn with_bounce_limit(mut self, count: u32, rule: BounceRule) -> Self {
+        self.bounce_limit = Some(BounceLimit::new(count, rule));
+        self
+    }
+
+    pub fn with_magnet(mut self, moment: f32, angle: f32) -> Self {
+        self.magnet = Some(Magnet::new(moment, angle));
+        self
+    }
+
+    pub fn with_pulse(mut self, pulse: Pulse) -> Self {
+        self.pulse = Some(pulse);
+        self
+    }
+
+    // accessible drawing colors balls by material from a color-blind safe palette and patterns them too
+    pub fn draw(&self, cam: &Camera, accessible: bool, d: &mut impl Renderer) {
+        let center = cam.project(self.center);
+        let radius = cam.scale(self.radius);
+
+        if accessible {
+            d.circle(center, radius, accessibility::color(&self.material));
+            Pattern::of(&self.material).draw(center, radius, d);
+            d.ring(center, (radius - 1.0).max(0.0), radius, Color::BLACK);
+        } else {
+            d.circle(center, radius, self.color);
+        }
+
+        if self.is_static {
+            d.ring(center, radius - 3.0, radius, Color::DARKGRAY);
+        }
+
+        // north half red, south half blue
+        if let Some(magnet) = self.magnet {
+            let axis = Vector2::new(magnet.angle.cos(), magnet.angle.sin()) * self.radius * 0.8;
+            d.line(center, cam.project(self.center + axis), 3.0, Color::RED);
+            d.line(center, cam.project(self.center - axis), 3.0, Color::BLUE);
+        }
+    }
+
+    pub fn draw_selection(&self, cam: &Camera, color: Color, d: &mut RaylibDrawHandle) {
+        let center = cam.project(self.center);
+        let radius = cam.scale(self.radius);
+
+        d.draw_ring(center, radius + 2.0, radius + 5.0, 0.0, 360.0, 48, color);
+
+        // velocity vector, scaled to a tenth of a second of travel
+        let tip = cam.project(self.center + self.velocity * 0.1);
+        d.draw_line_ex(center, tip, 2.0, color);
+        d.draw_circle_v(tip, 4.0, color);
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+        self.mass = material.mass_for(self.radius);
+    }
+
+    // mass follows from the material's density, and a magnet keeps its strength per area
+    pub fn set_radius(&mut self, radius: f32) {
+        if let Some(magnet) = &mut self.magnet {
+            magnet.moment *= (radius / self.radius).powi(2);
+        }
+        self.radius = radius;
+        self.mass = self.material.mass_for(radius);
+        self.freezing = self.freezing.max(10);
+    }
+
+    pub fn freeze(&mut self) {
+        self.velocity = Vector2::zero();
+        self.freezing = -1;
+    }
+
+    pub fn apply_impulse(&mut self, impulse: Vector2) {
+        if self.is_static {
+            return;
+        }
+
+        self.velocity += impulse / self.mass;
+        self.freezing = 10;
+    }
+
+    pub fn set_static(&mut self, is_static: bool) {
+        self.is_static = is_static;
+        self.velocity = Vector2::zero();
+        self.freezing = 10;
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        if self.is_static {
+            0.0
+        } else {
+            1.0 / self.mass
+        }
+    }
+
+    pub fn contains(&self, point: Vector2) -> bool {
+        self.center.distance_to(point) <= self.radius
+    }
+
+    pub fn is_awake(&self) -> bool {
+        self.freezing >= 0 && !self.is_static
+    }
+
+    fn wake_if_moving(&mut self, freezing_threshold: f32) {
+        if self.freezing < 0 && self.velocity.length() > freezing_threshold {
+            self.freezing = 10
+        }
+    }
+
+    // returns the magnitude of the normal impulse exchanged
+    fn apply_collision(&mut self, v: Vector2, other: &mut Ball) -> f32 {
+        let inv_self = self.inverse_mass();
+        let inv_other = other.inverse_mass();
+        let inv_total = inv_self + inv_other;
+        if inv_total == 0.0 {
+            return 0.0;
+        }
+
+        // static collision
+        self.center += v * (inv_self / inv_total);
+        other.center -= v * (inv_other / inv_total);
+
+        // dynamic collision
+        let normal = v.normalized();
+        let tangent = Vector2::new(-normal.y, normal.x);
+
+        let dot_tan_self = self.velocity.dot(tangent);
+        let dot_tan_other = other.velocity.dot(tangent);
+
+        let dot_normal_self = self.velocity.dot(normal);
+        let dot_normal_other = other.velocity.dot(normal);
+
+        // growing rims close the gap on their own, so they count towards the approach
+        let approach = dot_normal_other - dot_normal_self + self.growth + other.growth;
+
+        // already separating, which happens when the pair is resolved more than once per step
+        if approach <= 0.0 {
+            return 0.0;
+        }
+
+        let restitution = self.material.combine_restitution(&other.material);
+        let exchange = (1.0 + restitution) * approach / inv_total;
+
+        let momentum_self = dot_normal_self + exchange * inv_self;
+        let momentum_other = dot_normal_other - exchange * inv_other;
+
+        // coulomb friction, bounded by the normal impulse
+        let friction = self.material.combine_friction(&other.material) * exchange.abs();
+        let sliding = ((dot_tan_other - dot_tan_self) / inv_total).clamp(-friction, friction);
+
+        self.velocity = tangent * (dot_tan_self + sliding * inv_self) + normal * momentum_self;
+        other.velocity = tangent * (dot_tan_other - sliding * inv_other) + normal * momentum_other;
+        exchange.abs()
+    }
+
+    pub fn drag_force(&self, drag: f32) -> Vector2 {
+        // quadratic air drag over the ball's cross-section
+        -self.velocity * self.velocity.length() * drag * self.radius
+    }
+
+    pub fn integrate(&mut self, gravity: Vector2, dt: f32) {
+        if !self.is_awake() {
+            return;
+        }
+
+        self.velocity += gravity * dt;
+        self.center += self.velocity * dt;
+    }
+
+    // returns how fast the ball hit a wall, 0 if it did not; `damping` scales the restitution of the walls
+    pub fn settle(&mut self, bounds: Rectangle, wrap_x: bool, wrap_y: bool, damping: f32, freezing_threshold: f32) -> f32 {
+        if !self.is_awake() {
+            return 0.0;
+        }
+
+        let hit = self.resolve_bounding(bounds, wrap_x, wrap_y, damping);
+
+        if self.velocity.length() < freezing_threshold {
+            self.freezing -= 1;
+        }
+        hit
+    }
+
+    fn resolve_bounding(&mut self, bounds: Rectangle, wrap_x: bool, wrap_y: bool, damping: f32) -> f32 {
+        let (left, bottom, right, top) = (bounds.x, bounds.y, bounds.x + bounds.width, bounds.y + bounds.height);
+        let mid = Vector2::new((right + left) / 2.0, (top + bottom) / 2.0);
+        let half_bounding_size = Vector2::new(right - left, top - bottom) / 2.0 - Vector2::one() * self.radius;
+
+        let pos = self.center - mid;
+        let mut hit: f32 = 0.0;
+
+        if wrap_x {
+            // leaving through one side brings the ball back in through the other
+            if self.center.x > right {
+                self.center.x -= right - left;
+            } else if self.center.x < left {
+                self.center.x += right - left;
+            }
+        } else if pos.x.abs() > half_bounding_size.x {
+            self.center.x = half_bounding_size.x * pos.x.signum() + mid.x;
+            hit = hit.max(self.velocity.x.abs());
+            self.velocity.x *= -damping * self.material.restitution;
+        }
+
+        if wrap_y {
+            if self.center.y > top {
+                self.center.y -= top - bottom;
+            } else if self.center.y < bottom {
+                self.center.y += top - bottom;
+            }
+        } else if pos.y.abs() > half_bounding_size.y {
+            self.center.y = half_bounding_size.y * pos.y.signum() + mid.y;
+            hit = hit.max(self.velocity.y.abs());
+            self.velocity.y *= -damping * self.material.restitution;
+        }
+
+        hit
+    }
+
+    // the impulse of the collision if the balls touch
+    pub fn collide(&mut self, other: &mut Ball) -> Option<f32> {
+        self.collides(other).map(|v| self.apply_collision(v, other))
+    }
+
+    fn collides(&self, other: &Ball) -> Option<Vector2> {
+        let direction = other.center - self.center;
+        let intersection = direction.length() - (other.radius + self.radius);
+        if intersection > f32::EPSILON {
+            None
+        } else {
+            Some(direction.normalized() * intersection)
+        }
+    }
+}
+
+// picks the smallest ball under the point, ties going to the one drawn last (topmost)
+pub fn pick(balls: &[Ball], point: Vector2) -> Option<usize> {
+    let mut picked: Option<&Ball> = None;
+
+    for ball in balls.iter().filter(|b| b.contains(point)) {
+        match picked {
+            Some(p) if p.radius < ball.radius => {}
+            _ => picked = Some(ball),
+        }
+    }
+
+    picked.map(|b| b.id)
+}
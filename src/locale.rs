@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::settings;
+
+pub const ENGLISH: &str = "en";
+// shipped in the binary; a `<code>.json` in a `locales` directory next to the settings or in the working
+// directory takes precedence, so a language can be added or fixed without rebuilding
+const BUILT_IN: [(&str, &str); 2] = [
+    (ENGLISH, include_str!("../locales/en.json")),
+    ("es", include_str!("../locales/es.json")),
+];
+const DIR: &str = "locales";
+
+// the user facing text of one language, keyed like "hud.tool"; a key the language lacks falls back to
+// english, and one english lacks too shows as the key itself
+pub struct Locale {
+    strings: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn load(language: &str) -> Locale {
+        let english = parse(ENGLISH, &source(ENGLISH).unwrap_or_default());
+        let strings = match source(language) {
+            Some(json) if language != ENGLISH => parse(language, &json),
+            Some(_) => HashMap::new(),
+            None => {
+                eprintln!("no {} translation, using english", language);
+                HashMap::new()
+            }
+        };
+        Locale { strings, english }
+    }
+
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).or_else(|| self.english.get(key)).map_or(key, String::as_str)
+    }
+
+    // fills the text's `{}` with the arguments in turn, and `{0}`, `{1}`... by position for translations
+    // that need another order
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        let text = self.text(key);
+        let mut out = String::with_capacity(text.len());
+        let mut next = 0;
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}').map(|c| open + c) else {
+                out.push_str(&rest[open..]);
+                return out;
+            };
+            let index = match &rest[open + 1..close] {
+                "" => {
+                    next += 1;
+                    Some(next - 1)
+                }
+                digits => digits.parse::<usize>().ok(),
+            };
+            match index.and_then(|i| args.get(i)) {
+                Some(arg) => out.push_str(&arg.to_string()),
+                None => out.push_str(&rest[open..=close]),
+            }
+            rest = &rest[close + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+fn source(language: &str) -> Option<String> {
+    let file = format!("{}.json", language);
+    let dirs = [settings::dir().map(|d| d.join(DIR)), Some(PathBuf::from(DIR))];
+    dirs.iter().flatten()
+        .find_map(|dir| fs::read_to_string(dir.join(&file)).ok())
+        .or_else(|| BUILT_IN.iter().find(|(code, _)| *code == language).map(|(_, json)| json.to_string()))
+}
+
+fn parse(language: &str, json: &str) -> HashMap<String, String> {
+    serde_json::from_str(json).unwrap_or_else(|err| {
+        eprintln!("ignoring the {} translation: {}", language, err);
+        HashMap::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(strings: &[(&str, &str)], english: &[(&str, &str)]) -> Locale {
+        let map = |pairs: &[(&str, &str)]| pairs.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect();
+        Locale { strings: map(strings), english: map(english) }
+    }
+
+    #[test]
+    fn braces_take_the_arguments_in_turn() {
+        let locale = locale(&[("saved", "saved {} balls to {}")], &[]);
+        assert_eq!(locale.format("saved", &[&3, &"a.json"]), "saved 3 balls to a.json");
+    }
+
+    #[test]
+    fn numbered_braces_take_arguments_by_position() {
+        let locale = locale(&[("saved", "{1}: {0} bolas, {}")], &[]);
+        assert_eq!(locale.format("saved", &[&3, &"a.json"]), "a.json: 3 bolas, 3");
+    }
+
+    #[test]
+    fn missing_arguments_and_open_braces_are_left_as_written() {
+        let locale = locale(&[("odd", "{} of {} {7} {x} {")], &[]);
+        assert_eq!(locale.format("odd", &[&1]), "1 of {} {7} {x} {");
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english_then_the_key() {
+        let locale = locale(&[], &[("hello", "hello {}")]);
+        assert_eq!(locale.format("hello", &[&"there"]), "hello there");
+        assert_eq!(locale.text("nope"), "nope");
+    }
+
+    #[test]
+    fn built_in_translations_have_every_english_key() {
+        let english = parse(ENGLISH, BUILT_IN[0].1);
+        for (code, json) in &BUILT_IN[1..] {
+            let strings = parse(code, json);
+            let missing: Vec<_> = english.keys().filter(|k| !strings.contains_key(*k)).collect();
+            assert!(missing.is_empty(), "{} lacks {:?}", code, missing);
+        }
+    }
+}
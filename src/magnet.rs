@@ -0,0 +1,62 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Ball;
+
+// what ball magnets are made with, per unit of radius squared so the pull at contact does not depend on size
+pub const MOMENT_PER_AREA: f32 = 150.0;
+// spin is damped so dipoles settle into alignment instead of swinging forever
+const SPIN_DAMPING: f32 = 4.0;
+
+// a dipole of strength `moment` pointing along `angle`, free to turn; balls themselves do not rotate,
+// only the dipole does
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Magnet {
+    pub moment: f32,
+    pub angle: f32,
+    pub spin: f32,
+}
+
+impl Magnet {
+    pub fn new(moment: f32, angle: f32) -> Self {
+        Self { moment, angle, spin: 0.0 }
+    }
+
+    pub fn vector(&self) -> Vector2 {
+        Vector2::new(self.angle.cos(), self.angle.sin()) * self.moment
+    }
+
+    pub fn turn(&mut self, torque: f32, inertia: f32, dt: f32) {
+        self.spin += torque / inertia * dt;
+        self.spin *= (-SPIN_DAMPING * dt).exp();
+        self.angle += self.spin * dt;
+    }
+}
+
+// the force on `b` (the one on `a` being its opposite) and the torques on `a` and `b`, or None when further apart
+// than `range` times their touching distance; head to tail dipoles attract, side by side parallel ones repel
+pub fn interact(a: &Ball, ma: Vector2, b: &Ball, mb: Vector2, range: f32) -> Option<(Vector2, f32, f32)> {
+    let delta = b.center - a.center;
+    let touching = a.radius + b.radius;
+    let length = delta.length();
+    if length > touching * range || length <= f32::EPSILON {
+        return None;
+    }
+
+    // the balls may overlap a little mid-step, which must not blow the force up
+    let distance = length.max(touching);
+    let n = delta / length;
+    let (an, bn) = (ma.dot(n), mb.dot(n));
+
+    let force = (mb * an + ma * bn + n * ma.dot(mb) - n * (5.0 * an * bn)) * (3.0 / distance.powi(4));
+
+    let field_of_a = (n * (3.0 * an) - ma) / distance.powi(3);
+    let field_of_b = (n * (3.0 * bn) - mb) / distance.powi(3);
+
+    Some((force, cross(ma, field_of_b), cross(mb, field_of_a)))
+}
+
+fn cross(a: Vector2, b: Vector2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
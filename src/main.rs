@@ -1,167 +1,216 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
 use std::thread;
 use std::time::{Duration, Instant};
-use rand::random;
-use raylib::prelude::*;
 
-pub struct Camera {
-    pub position: Vector2,
+use raylib::prelude::*;
 
-    scale: f32,
-    scale_v: Vector2,
+use balls::camera_path::CameraPath;
+use balls::prelude::*;
+use balls::prelude::{Camera, Material};
+use balls::probe::{Probe, ProbeShape};
+use balls::render::Renderer;
+use balls::thruster::Exhaust;
+use balls::{export, factory, magnet, thermal};
+
+use autosave::{Autosave, Restore};
+use bullet_time::BulletTime;
+use calibration::Calibration;
+use cursor::Cursor;
+use editor::{ConnectTool, JointPopup, PopupResult, ProbeTool, Tool};
+use frame_graph::FrameGraph;
+use hud::Hud;
+use input_log::InputLog;
+use launcher::Launcher;
+use locale::Locale;
+use overlay::{Prediction, Streamlines};
+use presets::{Preset, PRESETS};
+use screensaver::Screensaver;
+use selection::Selection;
+use series::Series;
+use settings::{LastScene, Settings};
+use stats::Stats;
+use tilt::Tilt;
+
+mod autosave;
+mod bullet_time;
+mod calibration;
+mod cursor;
+mod diff;
+mod editor;
+mod frame_graph;
+mod gym_server;
+mod headless;
+mod hud;
+mod input_log;
+mod launcher;
+mod locale;
+mod metrics;
+mod overlay;
+mod presets;
+mod screensaver;
+mod selection;
+mod series;
+mod settings;
+mod stats;
+mod tilt;
+mod tui;
+
+// used when the monitor does not report its refresh rate
+const FPS_CAP: f32 = 120.0;
+// the last stretch of a capped frame is spun out instead of slept, sleeps overshoot by about this much
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+const IMPULSE: f32 = 20000.0;
+const SNAP_FLASH: f32 = 0.3;
+const AUTOSAVE_PATH: &str = "autosave.json";
+const EXPORT_PATH: &str = "scene_export.rs";
+const NETWORK_PATH: &str = "network.json";
+const IMPACT_THRESHOLD: f32 = 3000.0;
+// thrust of a keyboard rocket in multiples of its weight, and how fast it turns in radians per second
+const ROCKET_THRUST: f32 = 2.5;
+const ROCKET_TURN_RATE: f32 = 3.0;
+// the lowest temperature above zero, each step up doubling it
+const TEMPERATURE_STEP: f32 = 1000.0;
+// how far ahead H previews the selected balls
+const PREVIEW_STEPS: usize = 120;
+const PREVIEW_DT: f32 = 1.0 / 60.0;
+// how far N swings the selected balls' radii, as a fraction of their size, and how long a breath takes
+const BREATH_DEPTH: f32 = 0.3;
+const BREATH_PERIOD: f32 = 2.0;
+// the bubbles Z drops
+const SLOW_ZONE_SIZE: Vector2 = Vector2::new(160.0, 160.0);
+const SLOW_ZONE_SCALE: f32 = 0.25;
+
+// the preset's world, with the overlays it is meant to be watched with
+fn load_preset(preset: &Preset, streamlines: &mut Streamlines) -> World {
+    streamlines.enabled = preset.streamlines;
+    (preset.build)()
 }
 
-impl Camera {
-    pub fn new(position: Vector2, scale: f32) -> Self {
-        Self {
-            position,
-            scale,
-            scale_v: Vector2::one() * scale,
-        }
+fn probe_label(probe: &Probe, locale: &Locale) -> String {
+    match probe.shape {
+        ProbeShape::Line { .. } => locale.format("probe.rate", &[&probe.name, &format!("{:.1}", probe.value())]),
+        ProbeShape::Region { .. } => locale.format("probe.inside", &[&probe.name, &probe.value()]),
     }
+}
 
-    pub fn set_position(&mut self, position: Vector2) {
-        self.position = position;
+fn draw_inspector(ball: &Ball, locale: &Locale, hud: &Hud, d: &mut RaylibDrawHandle) {
+    let one = |v: f32| format!("{:.1}", v);
+    let yes = |b: bool| locale.text(if b { "common.yes" } else { "common.no" });
+    let lines = [
+        locale.format("inspector.title", &[&ball.id]),
+        locale.format("inspector.material", &[&locale.text(&format!("material.{}", ball.material.name))]),
+        locale.format("inspector.position", &[&one(ball.center.x), &one(ball.center.y)]),
+        locale.format("inspector.velocity", &[&one(ball.velocity.x), &one(ball.velocity.y)]),
+        locale.format("inspector.speed", &[&one(ball.velocity.length())]),
+        locale.format("inspector.radius", &[&one(ball.radius)]),
+        locale.format("inspector.mass", &[&one(ball.mass)]),
+        locale.format("inspector.frozen", &[&yes(ball.freezing < 0)]),
+        locale.format("inspector.static", &[&yes(ball.is_static)]),
+    ];
+
+    let x = 640 - 170;
+    let (left, _) = hud.place(x - 10, 0);
+    let height = ((20 + lines.len() as i32 * 14) as f32 * hud.scale) as i32;
+    d.draw_rectangle(left, 0, hud.screen.x as i32 - left, height, Color::new(0, 0, 0, 160));
+
+    for (i, line) in lines.iter().enumerate() {
+        hud.text(d, line, x, 10 + i as i32 * 14, Color::WHITE);
     }
+}
 
-    pub fn set_scale(&mut self, scale: f32) {
-        self.scale = scale;
-        self.scale_v = Vector2::new(scale, scale);
+fn apply_group_operations(rl: &RaylibHandle, settings: &Settings, world: &mut World, selection: &mut Selection) {
+    if selection.is_empty() {
+        return;
     }
 
-    pub fn invert_v(mut self) -> Self {
-        self.scale_v *= Vector2::new(1.0, -1.0);
-        self
+    if rl.is_key_pressed(settings.key(KeyboardKey::KEY_DELETE)) || rl.is_key_pressed(settings.key(KeyboardKey::KEY_BACKSPACE)) {
+        world.remove_balls(selection.ids());
+        selection.clear();
+        return;
     }
 
-    pub fn invert_h(mut self) -> Self {
-        self.scale_v *= Vector2::new(-1.0, 1.0);
-        self
-    }
+    let balls = &mut world.balls;
 
-    pub fn project(&self, v: Vector2) -> Vector2 {
-        (v * self.scale_v) + self.position
+    if rl.is_key_pressed(settings.key(KeyboardKey::KEY_S)) {
+        // pin everything unless the whole selection is already pinned, then release it
+        let pin = balls.iter().any(|b| selection.contains(b.id) && !b.is_static);
+        balls.iter_mut().filter(|b| selection.contains(b.id)).for_each(|b| b.set_static(pin));
+        return;
     }
 
-    pub fn scale(&self, v: f32) -> f32 {
-        v * self.scale
+    if rl.is_key_pressed(settings.key(KeyboardKey::KEY_M)) {
+        // magnetize everything unless the whole selection already is, then demagnetize it
+        let magnetize = balls.iter().any(|b| selection.contains(b.id) && b.magnet.is_none());
+        balls.iter_mut().filter(|b| selection.contains(b.id)).for_each(|b| b.magnet = magnetize.then(|| Magnet::new(b.radius * b.radius * magnet::MOMENT_PER_AREA, PI / 2.0)));
+        return;
     }
-}
 
-#[derive(Default, Copy, Clone)]
-pub struct Ball {
-    pub id: usize,
-    pub center: Vector2,
-    pub radius: f32,
-    pub mass: f32,
-    pub color: Color,
-    pub velocity: Vector2,
-    pub freezing: i32,
-}
-
-const DAMPING: f32 = 1.0;
-const FREEZING_THRESHOLD: f32 = 1e-4;
-const GRAVITY: Vector2 = Vector2::new(0.0, -980.0);
-const FPS_CAP: f32 = 120.0;
-
-
-impl Ball {
-    pub fn new(id: usize, center: Vector2, radius: f32, color: Color) -> Ball {
-        Ball {
-            id,
-            center,
-            radius,
-            color,
-            mass: radius,
-            velocity: Vector2::zero(),
-            freezing: 10,
-        }
+    if rl.is_key_pressed(settings.key(KeyboardKey::KEY_N)) {
+        // set everything breathing around its size unless the whole selection already pulses, then stop it
+        let breathe = balls.iter().any(|b| selection.contains(b.id) && b.pulse.is_none());
+        balls.iter_mut().filter(|b| selection.contains(b.id)).for_each(|b| b.pulse = breathe.then(|| Pulse::breathe(b.radius, b.radius * BREATH_DEPTH, BREATH_PERIOD)));
+        return;
     }
 
-    pub fn draw(&self, cam: &Camera, d: &mut RaylibDrawHandle) {
-        let center = cam.project(self.center);
-        let radius = cam.scale(self.radius);
+    let selected = balls.iter_mut().filter(|b| selection.contains(b.id));
 
-        d.draw_circle_v(center, radius, self.color);
+    if rl.is_key_pressed(settings.key(KeyboardKey::KEY_F)) {
+        selected.for_each(|b| b.freeze());
+        return;
     }
 
-    fn apply_collision(&mut self, v: Vector2, other: &mut Ball) {
-        // static collision
-        let half_d = v / 2.0;
-        self.center += half_d;
-        other.center -= half_d;
-
-        // dynamic collision
-        let normal = v.normalized();
-        let tangent = Vector2::new(-normal.y, normal.x);
-
-        let dot_tan_self = self.velocity.dot(tangent);
-        let dot_tan_other = other.velocity.dot(tangent);
-
-        let dot_normal_self = self.velocity.dot(normal);
-        let dot_normal_other = other.velocity.dot(normal);
-
-        let total_mass = self.mass + other.mass;
-
-        let momentum_self = (dot_normal_self * (self.mass - other.mass) + 2.0 * other.mass * dot_normal_other) / total_mass;
-        let momentum_other = (dot_normal_other * (other.mass - self.mass) + 2.0 * self.mass * dot_normal_self) / total_mass;
-
-        self.velocity = tangent * dot_tan_self + normal * momentum_self;
-        other.velocity = tangent * dot_tan_other + normal * momentum_other;
-
-        if other.freezing < 0 && other.velocity.length() > FREEZING_THRESHOLD {
-            other.freezing = 10
-        }
+    let material_keys = [KeyboardKey::KEY_ONE, KeyboardKey::KEY_TWO, KeyboardKey::KEY_THREE, KeyboardKey::KEY_FOUR, KeyboardKey::KEY_FIVE];
+    if let Some(i) = material_keys.iter().position(|&k| rl.is_key_pressed(settings.key(k))) {
+        selected.for_each(|b| b.set_material(Material::PRESETS[i]));
+        return;
     }
 
-    pub fn update(&mut self, dt: f32, balls: &mut [Ball]) {
-        if self.freezing < 0 {
-            return;
-        }
-
-        self.velocity += GRAVITY * dt;
-        self.center += self.velocity * dt;
-
-        for ball in balls {
-            if self.id == ball.id {
-                continue;
-            }
-            if let Some(v) = self.collides(ball) {
-                self.apply_collision(v, ball);
-            }
-        }
-
-        self.resolve_bounding(0.0, 0.0, 640.0, 480.0);
-
-        if self.velocity.length() < FREEZING_THRESHOLD {
-            self.freezing -= 1;
-        }
+    let arrows = [
+        (KeyboardKey::KEY_UP, Vector2::new(0.0, 1.0)),
+        (KeyboardKey::KEY_DOWN, Vector2::new(0.0, -1.0)),
+        (KeyboardKey::KEY_LEFT, Vector2::new(-1.0, 0.0)),
+        (KeyboardKey::KEY_RIGHT, Vector2::new(1.0, 0.0)),
+    ];
+    let direction = arrows.iter()
+        .filter(|(k, _)| rl.is_key_pressed(settings.key(*k)))
+        .fold(Vector2::zero(), |acc, (_, d)| acc + *d);
+
+    if direction != Vector2::zero() {
+        selected.for_each(|b| b.apply_impulse(direction.normalized() * IMPULSE));
     }
+}
 
-    fn resolve_bounding(&mut self, left: f32, bottom: f32, right: f32, top: f32) {
-        let mid = Vector2::new((right + left) / 2.0, (top + bottom) / 2.0);
-        let half_bounding_size = Vector2::new(right - left, top - bottom) / 2.0 - Vector2::one() * self.radius;
-
-        let pos = self.center - mid;
+fn save_network(world: &World, selected: &[usize], locale: &Locale) -> String {
+    let balls: Vec<usize> = if selected.is_empty() { world.balls.iter().map(|b| b.id).collect() } else { selected.to_vec() };
+    let network = Network::extract(world, &balls);
+    match network.save(NETWORK_PATH) {
+        Ok(()) => locale.format("status.network_saved", &[&network.links.len(), &NETWORK_PATH]),
+        Err(err) => locale.format("status.write_failed", &[&NETWORK_PATH, &err]),
+    }
+}
 
-        if pos.x.abs() > half_bounding_size.x {
-            self.center.x = half_bounding_size.x * pos.x.signum() + mid.x;
-            self.velocity.x *= -1.0 * DAMPING;
-        }
+fn load_network(world: &mut World, selected: &[usize], locale: &Locale) -> String {
+    let network = match Network::load(NETWORK_PATH) {
+        Ok(network) => network,
+        Err(err) => return locale.format("status.network_failed", &[&NETWORK_PATH, &err]),
+    };
 
-        if pos.y.abs() > half_bounding_size.y {
-            self.center.y = half_bounding_size.y * pos.y.signum() + mid.y;
-            self.velocity.y *= -1.0 * DAMPING;
-        }
+    // the selected balls stand in for the network's tags one by one, and there have to be enough of them
+    let tags = network.tags();
+    if !selected.is_empty() && selected.len() < tags.len() {
+        return locale.format("status.network_selection", &[&tags.len(), &selected.len()]);
     }
-
-    fn collides(&self, other: &Ball) -> Option<Vector2> {
-        let direction = other.center - self.center;
-        let intersection = direction.length() - (other.radius + self.radius);
-        if intersection > f32::EPSILON {
-            None
-        } else {
-            Some(direction.normalized() * intersection)
-        }
+    // with nothing selected it goes back where it was saved from, the names extract gave untagged balls
+    // binding to the balls they were made from
+    let bindings: HashMap<String, usize> = if selected.is_empty() {
+        tags.into_iter().filter(|tag| world.tagged(tag).is_none()).filter_map(|tag| Some((tag.to_string(), Network::id_in_name(tag)?))).collect()
+    } else {
+        tags.into_iter().zip(selected).map(|(tag, &id)| (tag.to_string(), id)).collect()
+    };
+    match network.instantiate(world, &bindings) {
+        Ok(ids) => locale.format("status.network_loaded", &[&ids.len(), &NETWORK_PATH]),
+        Err(err) => locale.format("status.network_failed", &[&NETWORK_PATH, &err]),
     }
 }
 
@@ -178,6 +227,18 @@ impl Clock {
         }
     }
 
+    pub fn frame_cap(fps: f32) -> Option<Duration> {
+        if fps > 0.0 { Some(Duration::from_micros((1e6 / fps) as u64)) } else { None }
+    }
+
+    pub fn set_fps(&mut self, fps: f32) {
+        self.frame_cap = Self::frame_cap(fps);
+    }
+
+    pub fn fps(&self) -> Option<f32> {
+        self.frame_cap.map(|cap| 1.0 / cap.as_secs_f32())
+    }
+
     pub fn tick(&mut self) -> f32 {
         if let Some(cap) = self.frame_cap {
             self.tick_capped(cap)
@@ -194,61 +255,585 @@ impl Clock {
     }
 
     pub fn tick_capped(&mut self, cap: Duration) -> f32 {
-        let mut now = Instant::now();
-        let mut delta = now - self.prev_tick;
+        let deadline = self.prev_tick + cap;
 
-        while delta < cap {
-            thread::yield_now(); // let os reschedule some other stuff
+        while Instant::now() + SPIN_MARGIN < deadline {
             thread::sleep(Duration::from_millis(1));
-
-            now = Instant::now();
-            delta = now - self.prev_tick;
+        }
+        while Instant::now() < deadline {
+            thread::yield_now(); // let os reschedule some other stuff
         }
 
+        let now = Instant::now();
+        let delta = now - self.prev_tick;
         self.prev_tick = now;
         (delta.as_micros() as f32) / 1e6
     }
 }
 
-fn main() {
-    let cam = Camera::new(Vector2::new(0.0, 480.0), 1.0).invert_v();
-    let mut balls = Vec::new();
+fn arg<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let i = args.iter().position(|a| a == name)?;
+    args.get(i + 1).map(String::as_str)
+}
+
+fn write_stats(stats: &Stats, path: &str, locale: &Locale) -> String {
+    match stats.write(path) {
+        Ok(()) => locale.format("status.stats_written", &[&path]),
+        Err(err) => locale.format("status.write_failed", &[&path, &err]),
+    }
+}
 
-    for _ in 0..5 {
-        fn rand_between(min: f32, max: f32) -> f32 {
-            min + (max - min) * random::<f32>()
+fn write_series(series: &Series, path: &str, locale: &Locale) -> String {
+    match series.write(path) {
+        Ok(()) => locale.format("status.series_written", &[&path]),
+        Err(err) => locale.format("status.write_failed", &[&path, &err]),
+    }
+}
+
+fn write_input_log(log: &InputLog, path: &str, locale: &Locale) -> String {
+    match log.write(path) {
+        Ok(()) => locale.format("status.input_log_written", &[&path]),
+        Err(err) => locale.format("status.write_failed", &[&path, &err]),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|a| a == "diff") {
+        std::process::exit(if diff::run(&args) { 0 } else { 1 });
+    }
+    if args.get(1).is_some_and(|a| a == "gym") {
+        gym_server::run(&args);
+        return;
+    }
+    if args.iter().any(|a| a == "--headless") {
+        headless::run(&args);
+        return;
+    }
+    if args.iter().any(|a| a == "--tui") {
+        if let Err(err) = tui::run(&args) {
+            eprintln!("terminal renderer failed: {}", err);
         }
+        return;
+    }
 
-        let radius = rand_between(20.0, 70.0);
-        let center = Vector2::new(rand_between(radius, 640.0 - radius), rand_between(radius, 480.0 - radius));
-        let color = Color::new(random(), random(), random(), 255);
+    // `--max-bounces <n>` makes balls dead on their nth wall impact, despawning them with `--despawn`
+    let rule = if args.iter().any(|a| a == "--despawn") { BounceRule::Despawn } else { BounceRule::Inelastic };
+    let bounce_limit = arg(&args, "--max-bounces").and_then(|v| v.parse::<u32>().ok()).map(|count| BounceLimit::new(count, rule));
 
-        balls.push(Ball::new(balls.len(), center, radius, color));
+    // the window, theme, key bindings, camera and scene of the last session
+    let mut settings = Settings::load();
+    // `--lang <code>` switches the language of the interface, and is remembered
+    if let Some(language) = arg(&args, "--lang") {
+        settings.language = language.to_string();
     }
+    let locale = Locale::load(&settings.language);
+
+    let home = Camera::new(Vector2::new(0.0, 480.0), 1.0).invert_v();
+    let mut cam = home;
+    cam.set_position(settings.camera_position);
+    cam.set_scale(settings.camera_scale);
+    let mut streamlines = Streamlines::default();
+    let mut world = load_preset(&PRESETS[0], &mut streamlines);
+    let mut camera_path = CameraPath::default();
+    let mut probes: Vec<Probe> = Vec::new();
+
+    // `--scene <path>` starts from a saved scene, playing its camera path if it has one; without it the last
+    // session's scene or preset is picked up again
+    let last_scene = match arg(&args, "--scene") {
+        Some(path) => Some(LastScene::File(path.to_string())),
+        None => settings.last_scene.clone(),
+    };
+    match &last_scene {
+        Some(LastScene::File(path)) => match Scene::load(path) {
+            Ok(scene) => {
+                world = scene.world;
+                camera_path = CameraPath::new(scene.camera);
+                probes = scene.probes;
+                settings.last_scene = last_scene.clone();
+            }
+            Err(err) => {
+                eprintln!("could not load {}: {}", path, err);
+                settings.last_scene = None;
+            }
+        },
+        Some(LastScene::Preset(name)) => match PRESETS.iter().find(|p| p.name == *name) {
+            Some(preset) => world = load_preset(preset, &mut streamlines),
+            None => settings.last_scene = None,
+        },
+        None => {}
+    }
+    world.bounce_limit = bounce_limit.or(world.bounce_limit);
 
     let (mut rl, thread) = raylib::init()
-        .size(640, 480)
+        .size(settings.window.0, settings.window.1)
+        .resizable()
         .title("Balls")
         .build();
 
-    let frame_cap = if FPS_CAP > 0.0 { Some(Duration::from_micros((1e6 / FPS_CAP) as u64)) } else { None };
-    let mut clock = Clock::new(frame_cap);
+    // `--fps <n>` pins the cap, 0 meaning uncapped; otherwise it follows the refresh rate of the window's monitor
+    let fps_override = arg(&args, "--fps").and_then(|v| v.parse::<f32>().ok());
+    // `--stats <path>` writes the run summary there on exit, F9 writes it at any time
+    let stats_path = arg(&args, "--stats").unwrap_or("stats.json");
+    let mut stats = Stats::default();
+    // `--csv <path>` does the same for the sampled time series, probes included
+    let series_path = arg(&args, "--csv").unwrap_or("run.csv");
+    let mut series = Series::default();
+    // F7 shows frame and step times as a rolling graph
+    let mut frame_graph = FrameGraph::default();
+    // `--record-input <path>` logs every key, button and mouse event with its frame and time, written there on exit and on F9
+    let input_path = arg(&args, "--record-input");
+    let mut input_log = input_path.map(|_| InputLog::new(&world));
+    let mut settle = Settle::default();
+    let mut clock = Clock::new(Clock::frame_cap(fps_override.unwrap_or(FPS_CAP)));
+    let mut monitor = None;
+    let mut selection = Selection::new();
+    let mut tool = Tool::Select;
+    let mut connect = ConnectTool::default();
+    let mut calibration = Calibration::default();
+    let mut probe_tool = ProbeTool::default();
+    let mut launcher = Launcher::default();
+    let mut popup: Option<JointPopup> = None;
+    let mut flippers: Vec<(Vec<usize>, f32)> = Vec::new();
+    let mut snaps: Vec<(Vector2, f32)> = Vec::new();
+    let mut status: Option<(String, f32)> = None;
+    let mut exhaust = Exhaust::default();
+    let mut screensaver = Screensaver::default();
+    let mut autosave = Autosave::new(AUTOSAVE_PATH);
+    // K turns the cursor into a pusher of `--cursor-radius` (20 by default), - and = resize it
+    // T toggles bullet time, `--bullet-time <impulse>` starts with it on and sets the impact that triggers it
+    let bullet_time_arg = arg(&args, "--bullet-time").and_then(|v| v.parse::<f32>().ok());
+    let mut bullet_time = BulletTime::new(bullet_time_arg.unwrap_or(IMPACT_THRESHOLD));
+    bullet_time.enabled = bullet_time_arg.is_some();
+    let mut preview = false;
+    let mut prediction = Prediction::default();
+    let mut tilt = Tilt::default();
+    let mut cursor = Cursor::new(arg(&args, "--cursor-radius").and_then(|v| v.parse::<f32>().ok()).unwrap_or(20.0));
 
     while !rl.window_should_close() {
+        let current = get_current_monitor();
+        if fps_override.is_none() && monitor != Some(current) && current < get_monitor_count() {
+            monitor = Some(current);
+            let refresh = get_monitor_refresh_rate(current);
+            clock.set_fps(if refresh > 0 { refresh as f32 } else { FPS_CAP });
+        }
+
         let dt = clock.tick();
+        let mouse = rl.get_mouse_position();
+        if let Some(log) = &mut input_log {
+            log.poll(&rl);
+        }
+
+        let screen = Vector2::new(rl.get_screen_width() as f32, rl.get_screen_height() as f32);
+        camera_path.advance(dt, &mut cam, screen);
+
+        if let Err(err) = autosave.update(screensaver.saved().unwrap_or(&world), dt) {
+            status = Some((locale.format("status.autosave_failed", &[&err]), 2.0));
+        }
+
+        let was_idle = screensaver.is_active();
+        screensaver.update(&mut rl, &mut world, &cam, dt);
+        if was_idle != screensaver.is_active() {
+            selection.clear();
+            connect.cancel();
+            calibration.cancel();
+            streamlines.clear();
+            settle.reset();
+            stats.reset_energy();
+        }
+
+        // the popup owns the mouse while it is open, the screensaver and the restore prompt own all input
+        if popup.is_none() && !screensaver.is_active() && !autosave.is_prompting() {
+            let preset_keys = [KeyboardKey::KEY_F1, KeyboardKey::KEY_F2, KeyboardKey::KEY_F3, KeyboardKey::KEY_F4, KeyboardKey::KEY_F5, KeyboardKey::KEY_F6];
+            if let Some(preset) = preset_keys.iter().position(|&k| rl.is_key_pressed(settings.key(k))).map(|i| &PRESETS[i]) {
+                world = load_preset(preset, &mut streamlines);
+                world.bounce_limit = bounce_limit.or(world.bounce_limit);
+                selection.clear();
+                calibration.cancel();
+                connect.cancel();
+                flippers.clear();
+                streamlines.clear();
+                exhaust.clear();
+                settle.reset();
+                stats.reset_energy();
+                camera_path = CameraPath::default();
+                cam = home;
+                settings.last_scene = Some(LastScene::Preset(preset.name.to_string()));
+                status = Some((locale.format("status.loaded", &[&locale.text(&format!("preset.{}", preset.name))]), 2.0));
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_F9)) {
+                let mut written = format!("{}, {}", write_stats(&stats, stats_path, &locale), write_series(&series, series_path, &locale));
+                if let (Some(log), Some(path)) = (&input_log, input_path) {
+                    written = format!("{}, {}", written, write_input_log(log, path, &locale));
+                }
+                status = Some((written, 2.0));
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_F7)) {
+                frame_graph.enabled = !frame_graph.enabled;
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_F8)) {
+                let snippet = export::rust_snippet(&world);
+                let copied = rl.set_clipboard_text(&snippet).is_ok();
+                status = Some((match std::fs::write(EXPORT_PATH, &snippet) {
+                    Ok(()) if copied => locale.format("status.exported_clipboard", &[&EXPORT_PATH]),
+                    Ok(()) => locale.format("status.exported", &[&EXPORT_PATH]),
+                    Err(err) => locale.format("status.write_failed", &[&EXPORT_PATH, &err]),
+                }, 2.0));
+            }
+            // Q saves the joints between the selected balls, or all of them, as a network; shift+Q puts it back
+            // onto the balls it names or, with a selection, onto the selected balls in the order they were picked
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_Q)) {
+                let shift = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+                status = Some((if shift { load_network(&mut world, selection.ids(), &locale) } else { save_network(&world, selection.ids(), &locale) }, 2.0));
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_V)) && !camera_path.is_empty() {
+                camera_path.restart();
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_O)) {
+                streamlines.enabled = !streamlines.enabled;
+                streamlines.clear();
+            }
+            if let Some(terrain) = &world.terrain {
+                let step = if rl.is_key_pressed(settings.key(KeyboardKey::KEY_RIGHT_BRACKET)) { 0.05 } else if rl.is_key_pressed(settings.key(KeyboardKey::KEY_LEFT_BRACKET)) { -0.05 } else { 0.0 };
+                if step != 0.0 {
+                    world.set_friction((terrain.friction + step).clamp(0.0, 1.5));
+                }
+            }
+            // , and . cool and heat the thermal bath, which is off at zero
+            let heat = if rl.is_key_pressed(settings.key(KeyboardKey::KEY_PERIOD)) { 1.0 } else if rl.is_key_pressed(settings.key(KeyboardKey::KEY_COMMA)) { -1.0 } else { 0.0 };
+            if heat != 0.0 {
+                let temperature = world.thermostat.temperature;
+                world.thermostat.temperature = if heat > 0.0 { (temperature * 2.0).max(TEMPERATURE_STEP) } else if temperature > TEMPERATURE_STEP { temperature / 2.0 } else { 0.0 };
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_T)) {
+                bullet_time.toggle();
+            }
+            // A draws for color-blind eyes with larger text
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_A)) {
+                settings.accessible = !settings.accessible;
+            }
+            // D switches between the light and dark theme
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_D)) {
+                settings.theme = settings.theme.toggle();
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_H)) {
+                preview = !preview;
+            }
+            // Z drops a slow motion bubble under the mouse, or pops the one already there
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_Z)) {
+                let point = cam.unproject(mouse);
+                if let Some(i) = world.time_zones.iter().position(|z| z.contains(point)) {
+                    world.time_zones.remove(i);
+                } else {
+                    let corner = point - SLOW_ZONE_SIZE / 2.0;
+                    world.time_zones.push(TimeZone::new(Rectangle::new(corner.x, corner.y, SLOW_ZONE_SIZE.x, SLOW_ZONE_SIZE.y), SLOW_ZONE_SCALE));
+                }
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_K)) {
+                cursor.toggle(&mut world);
+            }
+            if cursor.enabled {
+                let step = if rl.is_key_pressed(settings.key(KeyboardKey::KEY_EQUAL)) { 5.0 } else if rl.is_key_pressed(settings.key(KeyboardKey::KEY_MINUS)) { -5.0 } else { 0.0 };
+                cursor.resize(step);
+                cursor.update(&mut world, cam.unproject(mouse), dt);
+            }
+            // X mounts a thruster on the selected ball or takes it off again; I fires every thruster, J and L steer them
+            if let Some(id) = selection.primary().filter(|_| rl.is_key_pressed(settings.key(KeyboardKey::KEY_X))) {
+                if let Some(i) = world.thrusters.iter().position(|t| t.ball == id) {
+                    world.thrusters.remove(i);
+                } else if let Some(ball) = world.ball(id) {
+                    let force = ball.mass * world.gravity.length() * ROCKET_THRUST;
+                    world.thrusters.push(Thruster::new(id, PI / 2.0, force));
+                }
+            }
+            let throttle = if rl.is_key_down(settings.key(KeyboardKey::KEY_I)) { 1.0 } else { 0.0 };
+            let turn = if rl.is_key_down(settings.key(KeyboardKey::KEY_J)) { 1.0 } else if rl.is_key_down(settings.key(KeyboardKey::KEY_L)) { -1.0 } else { 0.0 };
+            for thruster in &mut world.thrusters {
+                thruster.throttle = throttle;
+                thruster.angle += turn * ROCKET_TURN_RATE * dt;
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_E)) {
+                tool = tool.next();
+                connect.cancel();
+                calibration.cancel();
+                probe_tool.cancel();
+                launcher.cancel();
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_C)) {
+                factory::chain(&mut world, cam.unproject(mouse), 8, 8.0, PI / 4.0);
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_R)) {
+                factory::ragdoll(&mut world, cam.unproject(mouse), 60.0);
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_P)) {
+                for facing in [1.0, -1.0] {
+                    let pivot = Vector2::new(320.0 - facing * 140.0, 80.0);
+                    flippers.push((factory::flipper(&mut world, pivot, 110.0, facing), facing));
+                }
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_W)) {
+                factory::windmill(&mut world, cam.unproject(mouse), 4, 80.0, 2.0);
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_G)) {
+                factory::gears(&mut world, cam.unproject(mouse), 40.0, 1.5, 2.0);
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_U)) {
+                factory::pulley(&mut world, cam.unproject(mouse), 120.0, 120.0);
+            }
+            if rl.is_key_pressed(settings.key(KeyboardKey::KEY_B)) {
+                let y = cam.unproject(mouse).y;
+                factory::bridge(&mut world, Vector2::new(40.0, y), Vector2::new(600.0, y), 8.0, 3000.0);
+            }
+
+            match tool {
+                Tool::Select => {
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON) {
+                        selection.begin_drag(mouse);
+                    }
+                    if rl.is_mouse_button_released(MouseButton::MOUSE_LEFT_BUTTON) {
+                        let additive = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+                        selection.end_drag(mouse, &cam, &world.balls, additive);
+                    }
+
+                    apply_group_operations(&rl, &settings, &mut world, &mut selection);
+
+                    // scrolling over a ball grows or shrinks it by a tenth per notch
+                    let wheel = rl.get_mouse_wheel_move();
+                    if let Some(id) = pick(&world.balls, cam.unproject(mouse)).filter(|_| wheel != 0.0) {
+                        let radius = world.ball(id).map_or(0.0, |b| b.radius * 1.1f32.powf(wheel));
+                        world.resize_ball(id, radius);
+                    }
+                }
+                Tool::Connect => {
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON) {
+                        popup = connect.click(&mut world, cam.unproject(mouse), &cam);
+                    }
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_RIGHT_BUTTON) {
+                        connect.cancel();
+                    }
+                }
+                Tool::Calibrate => {
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON) {
+                        calibration.click(&mut world, cam.unproject(mouse));
+                    }
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_RIGHT_BUTTON) {
+                        calibration.cancel();
+                    }
+                }
+                Tool::Probe => {
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON) {
+                        probe_tool.begin_drag(mouse);
+                    }
+                    if rl.is_mouse_button_released(MouseButton::MOUSE_LEFT_BUTTON) {
+                        let region = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+                        probe_tool.end_drag(mouse, &cam, region, &mut probes);
+                    }
+                    // right click takes the newest probe away
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_RIGHT_BUTTON) {
+                        probes.pop();
+                    }
+                }
+                // up and down change how many balls a volley has, left and right how wide it fans out
+                Tool::Launch => {
+                    let count = if rl.is_key_pressed(settings.key(KeyboardKey::KEY_UP)) { 1 } else if rl.is_key_pressed(settings.key(KeyboardKey::KEY_DOWN)) { -1 } else { 0 };
+                    let spread = if rl.is_key_pressed(settings.key(KeyboardKey::KEY_RIGHT)) { 0.1 } else if rl.is_key_pressed(settings.key(KeyboardKey::KEY_LEFT)) { -0.1 } else { 0.0 };
+                    launcher.resize_volley(count, spread);
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON) {
+                        launcher.begin(mouse);
+                    }
+                    if rl.is_mouse_button_released(MouseButton::MOUSE_LEFT_BUTTON) {
+                        launcher.release(&mut world, mouse, &cam);
+                    }
+                    if rl.is_mouse_button_pressed(MouseButton::MOUSE_RIGHT_BUTTON) {
+                        launcher.cancel();
+                    }
+                }
+            }
+        } else {
+            cursor.release(&mut world);
+        }
+
+        let flipping = rl.is_key_down(settings.key(KeyboardKey::KEY_SPACE));
+        for (joints, facing) in &flippers {
+            factory::drive_flipper(&mut world, joints, *facing, flipping);
+        }
+
+        tilt.update(&mut world);
+
+        world.impact_threshold = bullet_time.impact_threshold();
+        bullet_time.update(dt);
+
+        let step_start = Instant::now();
+        world.step(dt * bullet_time.scale());
+        let step_time = step_start.elapsed();
+        stats.record(&world, step_time);
+        frame_graph.record(dt, step_time);
+        streamlines.record(&world);
+        for probe in &mut probes {
+            probe.record(&world, dt * bullet_time.scale());
+        }
+        series.record(&world, &probes, dt * bullet_time.scale());
+        if let Some(report) = settle.record(&world, dt * bullet_time.scale()) {
+            status = Some((locale.format("status.settled", &[&format!("{:.1}", report.time), &format!("{:.0}", report.pile_height)]), 4.0));
+        }
+        exhaust.update(&world, dt * bullet_time.scale());
+        if let Some(log) = &mut input_log {
+            log.advance(dt * bullet_time.scale());
+        }
+        calibration.record(&world);
+
+        for event in world.events.drain(..) {
+            match event {
+                Event::JointBroken { joint, a, b, position, impulse } => {
+                    snaps.push((position, SNAP_FLASH));
+                    status = Some((locale.format("status.joint_snapped", &[&joint, &a, &b, &format!("{:.0}", impulse)]), 2.0));
+                }
+                Event::BouncesExhausted { ball, position, rule: BounceRule::Despawn } => {
+                    selection.remove(ball);
+                    snaps.push((position, SNAP_FLASH));
+                }
+                Event::Impact { a, b, position, impulse } => {
+                    bullet_time.trigger();
+                    snaps.push((position, SNAP_FLASH));
+                    status = Some((locale.format("status.impact", &[&a, &b, &format!("{:.0}", impulse)]), 2.0));
+                }
+                _ => {}
+            }
+        }
+
+        let hud = Hud::new(settings.accessible, screen);
         let mut d = rl.begin_drawing(&thread);
 
-        d.clear_background(Color::WHITE);
+        d.clear_background(settings.theme.background());
 
-        for i in 0..balls.len() {
-            let mut ball = balls[i];
+        streamlines.draw(&world, &cam, &mut d);
+        exhaust.draw(&world, &cam, &mut d);
+        world.draw(&cam, settings.accessible, &mut d);
 
-            ball.update(dt, &mut balls);
-            ball.draw(&cam, &mut d);
+        if screensaver.is_active() {
+            screensaver.draw(&locale, &hud, &mut d);
+            continue;
+        }
 
-            balls[i] = ball;
+        selection.draw(&cam, &world.balls, mouse, &mut d);
+        // runs a fork of the world ahead, so joints, collisions and fields all bend the path
+        if preview && !selection.is_empty() {
+            for path in prediction.paths(&world, selection.ids(), &[], PREVIEW_STEPS, PREVIEW_DT) {
+                for pair in path.windows(2) {
+                    d.line(cam.project(pair[0]), cam.project(pair[1]), 1.0, Color::SKYBLUE);
+                }
+            }
+        }
+        cursor.draw(&world, &cam, &mut d);
+        for (i, probe) in probes.iter().enumerate() {
+            probe.draw(&cam, &mut d);
+            hud.text(&mut d, &probe_label(probe, &locale), 640 - 150, 10 + 14 * i as i32, settings.theme.text());
+        }
+        if tool == Tool::Probe {
+            probe_tool.draw(mouse, &mut d);
+        }
+        if tool == Tool::Launch {
+            launcher.draw(&world, &cam, mouse, &mut d);
+            hud.text(&mut d, &locale.format("hud.volley", &[&launcher.count, &format!("{:.0}", launcher.spread.to_degrees())]), 10, 376, settings.theme.text());
+        }
+        connect.draw(&world, &cam, &mut d);
+        if tool == Tool::Calibrate {
+            calibration.draw(&world, &cam, &locale, &hud, &mut d);
+        }
+
+        snaps.retain_mut(|(position, time)| {
+            *time -= dt;
+            d.draw_circle_lines(cam.project(*position).x as i32, cam.project(*position).y as i32, cam.scale(20.0 * (1.0 - *time / SNAP_FLASH)), Color::RED);
+            *time > 0.0
+        });
+
+        if selection.ids().len() > 1 {
+            hud.text(&mut d, &locale.format("hud.selected", &[&selection.ids().len()]), 10, 24, settings.theme.text());
+        } else if let Some(ball) = selection.primary().and_then(|id| world.ball(id)) {
+            draw_inspector(ball, &locale, &hud, &mut d);
+        }
+
+        match autosave.draw_prompt(&locale, &mut d) {
+            Ok(Restore::Pending) | Ok(Restore::Discard) => {}
+            Ok(Restore::Kept(path)) => status = Some((locale.format("status.autosave_kept", &[&path]), 2.0)),
+            Ok(Restore::Accept(restored)) => {
+                // nothing picked, aimed or built for the old world applies to the restored one
+                world = *restored;
+                selection.clear();
+                calibration.cancel();
+                connect.cancel();
+                probe_tool.cancel();
+                launcher.cancel();
+                flippers.clear();
+                probes.clear();
+                streamlines.clear();
+                exhaust.clear();
+                prediction = Prediction::default();
+                settle.reset();
+                stats.reset_energy();
+                status = Some((locale.text("status.restored").to_string(), 2.0));
+            }
+            Err(err) => status = Some((locale.format("status.restore_failed", &[&err]), 2.0)),
         }
 
-        d.draw_text(format!("FPS: {}", (1.0 / dt) as i32).as_str(), 10, 10, 10, Color::RED);
+        if let Some(p) = &mut popup {
+            match p.draw(&locale, &mut d) {
+                PopupResult::Open => {}
+                PopupResult::Cancel => popup = None,
+                PopupResult::Create(joint) => {
+                    world.add_joint(joint);
+                    popup = None;
+                }
+            }
+        }
+
+        frame_graph.draw(&locale, &hud, &mut d);
+        let cap = clock.fps().map_or(locale.text("hud.uncapped").to_string(), |fps| locale.format("hud.cap", &[&format!("{:.0}", fps)]));
+        hud.text(&mut d, &locale.format("hud.fps", &[&((1.0 / dt) as i32), &cap]), 10, 10, Color::RED);
+        hud.text(&mut d, &locale.format("hud.tool", &[&locale.text(&format!("tool.{}", tool.name()))]), 10, 460, settings.theme.text());
+        if world.thermostat.temperature > 0.0 {
+            hud.text(&mut d, &locale.format("hud.bath", &[&format!("{:.0}", world.thermostat.temperature), &format!("{:.0}", thermal::measure(&world.balls))]), 10, 390, settings.theme.text());
+        }
+        if bullet_time.enabled {
+            hud.text(&mut d, &locale.format("hud.bullet_time", &[&format!("{:.0}", bullet_time.threshold), &format!("{:.2}", bullet_time.scale())]), 10, 404, settings.theme.text());
+        }
+        if !camera_path.is_empty() {
+            let state = locale.text(if camera_path.playing { "hud.playing" } else { "hud.done" });
+            hud.text(&mut d, &locale.format("hud.camera_path", &[&format!("{:.1}", camera_path.time().min(camera_path.duration())), &format!("{:.1}", camera_path.duration()), &state]), 10, 418, settings.theme.text());
+        }
+        if let Some(terrain) = &world.terrain {
+            // a frictional pile can hold a slope up to atan(mu)
+            let repose = terrain.friction.atan().to_degrees();
+            hud.text(&mut d, &locale.format("hud.friction", &[&format!("{:.2}", terrain.friction), &format!("{:.0}", repose)]), 10, 432, settings.theme.text());
+        }
+
+        if let Some((message, time)) = &mut status {
+            hud.text(&mut d, message, 10, 446, Color::RED);
+            *time -= dt;
+            if *time <= 0.0 {
+                status = None;
+            }
+        }
+    }
+
+    settings.window = (rl.get_screen_width(), rl.get_screen_height());
+    settings.camera_position = cam.position;
+    settings.camera_scale = cam.scale(1.0);
+    if let Err(err) = settings.save() {
+        eprintln!("could not save the settings: {}", err);
+    }
+
+    if let Err(err) = autosave.clear() {
+        eprintln!("could not remove the autosave: {}", err);
+    }
+
+    if arg(&args, "--stats").is_some() {
+        println!("{}", write_stats(&stats, stats_path, &locale));
+    }
+    if arg(&args, "--csv").is_some() {
+        println!("{}", write_series(&series, series_path, &locale));
+    }
+    if let (Some(log), Some(path)) = (&input_log, input_path) {
+        println!("{}", write_input_log(log, path, &locale));
     }
 }
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct Material {
+    pub name: &'static str,
+    pub restitution: f32,
+    pub density: f32,
+    pub friction: f32,
+}
+
+impl Material {
+    pub const ELASTIC: Material = Material { name: "elastic", restitution: 1.0, density: 1.0, friction: 0.0 };
+    pub const RUBBER: Material = Material { name: "rubber", restitution: 0.85, density: 1.1, friction: 0.9 };
+    pub const WOOD: Material = Material { name: "wood", restitution: 0.5, density: 0.7, friction: 0.4 };
+    pub const STEEL: Material = Material { name: "steel", restitution: 0.6, density: 7.8, friction: 0.2 };
+    pub const SAND: Material = Material { name: "sand", restitution: 0.1, density: 1.6, friction: 0.6 };
+
+    pub const PRESETS: [Material; 5] = [Self::ELASTIC, Self::RUBBER, Self::WOOD, Self::STEEL, Self::SAND];
+
+    pub fn new(name: &'static str, restitution: f32, density: f32, friction: f32) -> Self {
+        Self { name, restitution, density, friction }
+    }
+
+    pub fn mass_for(&self, radius: f32) -> f32 {
+        radius * self.density
+    }
+
+    pub fn combine_restitution(&self, other: &Material) -> f32 {
+        self.restitution.min(other.restitution)
+    }
+
+    pub fn combine_friction(&self, other: &Material) -> f32 {
+        (self.friction * other.friction).sqrt()
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self::ELASTIC
+    }
+}
+
+// saved with an owned name, which is mapped back onto a preset's when loading
+#[derive(Serialize, Deserialize)]
+struct SavedMaterial {
+    name: String,
+    restitution: f32,
+    density: f32,
+    friction: f32,
+}
+
+impl From<Material> for SavedMaterial {
+    fn from(m: Material) -> Self {
+        Self { name: m.name.to_string(), restitution: m.restitution, density: m.density, friction: m.friction }
+    }
+}
+
+impl From<SavedMaterial> for Material {
+    fn from(m: SavedMaterial) -> Self {
+        let name = Material::PRESETS.iter().find(|p| p.name == m.name).map_or("custom", |p| p.name);
+        Material { name, restitution: m.restitution, density: m.density, friction: m.friction }
+    }
+}
+
+impl Serialize for Material {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedMaterial::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Material {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SavedMaterial::deserialize(deserializer).map(Material::from)
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use balls::world::World;
+
+// the latest numbers from the simulation loop, rendered in the prometheus text format
+#[derive(Default, Clone, Copy)]
+pub struct Metrics {
+    steps: u64,
+    step_seconds: f64,
+    step_seconds_sum: f64,
+    bodies: usize,
+    joints: usize,
+    contacts: usize,
+}
+
+impl Metrics {
+    pub fn record(&mut self, world: &World, step_time: Duration) {
+        self.steps += 1;
+        self.step_seconds = step_time.as_secs_f64();
+        self.step_seconds_sum += self.step_seconds;
+        self.bodies = world.balls.len();
+        self.joints = world.joints.len();
+        self.contacts = world.contacts;
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+            out += &format!("# HELP balls_{name} {help}\n# TYPE balls_{name} {kind}\nballs_{name} {value}\n");
+        };
+
+        metric("steps_total", "counter", "Simulation steps taken.", self.steps as f64);
+        metric("step_seconds", "gauge", "Duration of the last step.", self.step_seconds);
+        metric("step_seconds_total", "counter", "Time spent stepping, divide by steps_total for the mean.", self.step_seconds_sum);
+        metric("bodies", "gauge", "Balls in the world.", self.bodies as f64);
+        metric("joints", "gauge", "Joints in the world.", self.joints as f64);
+        metric("contacts", "gauge", "Ball pairs touching during the last step.", self.contacts as f64);
+        out
+    }
+}
+
+// answers GET /metrics on `address` from a background thread
+pub fn serve(address: &str, metrics: Arc<Mutex<Metrics>>) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // each client on its own thread, so a slow or misbehaving one only holds up its own response
+            let metrics = metrics.clone();
+            thread::spawn(move || respond(stream, &metrics));
+        }
+    });
+
+    Ok(())
+}
+
+fn respond(mut stream: TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request)?;
+
+    // skip the headers, nothing in them matters here
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let path = request.split_whitespace().nth(1).unwrap_or("");
+    let (status, body) = if path == "/metrics" {
+        let snapshot = *metrics.lock().unwrap();
+        ("200 OK", snapshot.render())
+    } else {
+        ("404 Not Found", "try /metrics\n".to_string())
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
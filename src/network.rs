@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::coupling::Coupling;
+use crate::joint::{AngleLimit, Joint, JointKind, Motor};
+use crate::world::World;
+
+// a joint between the balls tagged `a` and `b`; `parent` is the position of the parent link in the network
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Link {
+    pub a: String,
+    pub b: String,
+    pub length: f32,
+    pub kind: JointKind,
+    #[serde(default)]
+    pub parent: Option<usize>,
+    #[serde(default)]
+    pub reference: f32,
+    #[serde(default)]
+    pub limit: Option<AngleLimit>,
+    #[serde(default)]
+    pub motor: Option<Motor>,
+    #[serde(default)]
+    pub break_impulse: Option<f32>,
+}
+
+impl Link {
+    pub fn new(a: &str, b: &str, length: f32, kind: JointKind) -> Self {
+        Self {
+            a: a.to_string(),
+            b: b.to_string(),
+            length,
+            kind,
+            parent: None,
+            reference: 0.0,
+            limit: None,
+            motor: None,
+            break_impulse: None,
+        }
+    }
+
+    pub fn with_parent(mut self, parent: usize) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn with_reference(mut self, reference: f32) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    pub fn with_limit(mut self, min: f32, max: f32) -> Self {
+        self.limit = Some(AngleLimit { min, max });
+        self
+    }
+
+    pub fn with_motor(mut self, speed: f32, max_torque: f32) -> Self {
+        self.motor = Some(Motor { speed, max_torque });
+        self
+    }
+
+    pub fn with_break_impulse(mut self, impulse: f32) -> Self {
+        self.break_impulse = Some(impulse);
+        self
+    }
+}
+
+// the joints and couplings of a machine without its balls, which it names by tag, so the same machine can be
+// saved once and put onto other balls or merged into another scene
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Network {
+    pub links: Vec<Link>,
+    // `first` and `second` are positions in `links`
+    #[serde(default)]
+    pub couplings: Vec<Coupling>,
+}
+
+impl Network {
+    pub fn new(links: Vec<Link>, couplings: Vec<Coupling>) -> Self {
+        Self { links, couplings }
+    }
+
+    // the joints with both ends among `balls` and the couplings between those; a ball without a tag is named
+    // after its id in the network only, by a name no ball in the world has, so putting the network back onto
+    // it takes binding that name
+    pub fn extract(world: &World, balls: &[usize]) -> Network {
+        let joints: Vec<Joint> = world.joints.iter().filter(|j| balls.contains(&j.a) && balls.contains(&j.b)).copied().collect();
+        let position = |id: usize| joints.iter().position(|j| j.id == id);
+
+        let mut names: HashMap<usize, String> = HashMap::new();
+        let mut name = |id: usize| {
+            if let Some(name) = names.get(&id) {
+                return name.clone();
+            }
+            let name = world.tag_of(id).map_or_else(|| unused_name(world, &names, id), str::to_string);
+            names.insert(id, name.clone());
+            name
+        };
+        let links = joints.iter()
+            .map(|j| Link {
+                a: name(j.a),
+                b: name(j.b),
+                length: j.length,
+                kind: j.kind,
+                parent: j.parent.and_then(position),
+                reference: j.reference,
+                limit: j.limit,
+                motor: j.motor,
+                break_impulse: j.break_impulse,
+            })
+            .collect();
+
+        let couplings = world.couplings.iter()
+            .filter_map(|c| Some(Coupling { first: position(c.first)?, second: position(c.second)?, ..*c }))
+            .collect();
+        Network { links, couplings }
+    }
+
+    // the tags in the order the links first name them
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags = Vec::new();
+        for tag in self.links.iter().flat_map(|l| [l.a.as_str(), l.b.as_str()]) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    // adds the network to the world between the balls its tags name, a tag in `bindings` going onto the ball
+    // it is bound to instead, so the machine can go onto other balls; nothing is added unless every tag is
+    // found. returns the new joints' ids; a tag or binding naming no ball is invalid input, a link index out of
+    // the network invalid data
+    pub fn instantiate(&self, world: &mut World, bindings: &HashMap<String, usize>) -> io::Result<Vec<usize>> {
+        let resolve = |tag: &str| match bindings.get(tag) {
+            Some(&id) if world.ball(id).is_some() => Ok(id),
+            Some(&id) => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("no ball #{} for {}", id, tag))),
+            None => world.tagged(tag).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("no ball tagged {}", tag))),
+        };
+        let ends = self.links.iter().map(|l| Ok((resolve(&l.a)?, resolve(&l.b)?))).collect::<io::Result<Vec<_>>>()?;
+        if let Some(bad) = self.links.iter().flat_map(|l| l.parent).chain(self.couplings.iter().flat_map(|c| [c.first, c.second])).find(|&i| i >= self.links.len()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("no link {} in the network", bad)));
+        }
+
+        // parents are filled in once every link has its id
+        let ids: Vec<usize> = self.links.iter().zip(&ends)
+            .map(|(l, &(a, b))| {
+                let mut joint = Joint::new(a, b, l.length, l.kind);
+                joint.reference = l.reference;
+                joint.limit = l.limit;
+                joint.motor = l.motor;
+                joint.break_impulse = l.break_impulse;
+                world.add_joint(joint)
+            })
+            .collect();
+        for (link, &id) in self.links.iter().zip(&ids) {
+            if let (Some(parent), Some(joint)) = (link.parent, world.joints.iter_mut().find(|j| j.id == id)) {
+                joint.parent = Some(ids[parent]);
+            }
+        }
+        for coupling in &self.couplings {
+            world.add_coupling(Coupling { first: ids[coupling.first], second: ids[coupling.second], ..*coupling });
+        }
+        Ok(ids)
+    }
+
+    // the ball id in a name extract made up for an untagged ball
+    pub fn id_in_name(name: &str) -> Option<usize> {
+        name.strip_prefix("ball")?.split('_').next()?.parse().ok()
+    }
+
+    pub fn load(path: &str) -> io::Result<Network> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+// `ball<id>`, suffixed until neither the world's tags nor the names picked so far have it
+fn unused_name(world: &World, names: &HashMap<usize, String>, id: usize) -> String {
+    let taken = |name: &str| world.tags.contains_key(name) || names.values().any(|n| n == name);
+    let base = format!("ball{}", id);
+    (1..).map(|n| if n == 1 { base.clone() } else { format!("{}_{}", base, n) }).find(|name| !taken(name)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raylib::prelude::{Color, Vector2};
+
+    use crate::Ball;
+    
+    // two pendulums hung from a shared pivot, the second geared to the first
+    fn machine() -> (World, Vec<usize>) {
+        let mut world = World::new();
+        let balls: Vec<usize> = [(400.0, 100.0), (400.0, 200.0), (500.0, 200.0)].iter().map(|&(x, y)| world.add_ball(Ball::new(Vector2::new(x, y), 10.0, Color::RED))).collect();
+        world.tag_ball(balls[0], "pivot");
+        let first = world.add_joint(Joint::new(balls[0], balls[1], 100.0, JointKind::Distance));
+        let mut second = Joint::new(balls[1], balls[2], 100.0, JointKind::Distance);
+        second.parent = Some(first);
+        let second = world.add_joint(second);
+        world.add_coupling(Coupling::gear(first, second, 2.0));
+        (world, balls)
+    }
+
+    #[test]
+    fn extracting_leaves_the_world_untagged() {
+        let (world, balls) = machine();
+        let network = Network::extract(&world, &balls);
+        assert_eq!(network.tags(), ["pivot", "ball1", "ball2"]);
+        assert_eq!(world.tags.len(), 1);
+    }
+
+    #[test]
+    fn made_up_names_skip_tags_already_in_use() {
+        let (mut world, balls) = machine();
+        world.tag_ball(balls[0], "ball1");
+        let network = Network::extract(&world, &balls);
+        assert_eq!(network.tags(), ["ball1", "ball1_2", "ball2"]);
+        assert_eq!(Network::id_in_name("ball1_2"), Some(1));
+    }
+
+    #[test]
+    fn extract_then_instantiate_rebuilds_the_machine() {
+        let (world, balls) = machine();
+        let network = Network::extract(&world, &balls);
+
+        let mut copy = World::new();
+        let moved: Vec<usize> = world.balls.iter().map(|b| copy.add_ball(Ball::new(b.center + Vector2::new(0.0, 50.0), b.radius, Color::RED))).collect();
+        let bindings = network.tags().into_iter().zip(&moved).map(|(tag, &id)| (tag.to_string(), id)).collect();
+        let ids = network.instantiate(&mut copy, &bindings).unwrap();
+
+        assert_eq!(ids.len(), 2);
+        assert_eq!((copy.joints[0].a, copy.joints[0].b), (moved[0], moved[1]));
+        assert_eq!((copy.joints[1].a, copy.joints[1].b), (moved[1], moved[2]));
+        assert_eq!(copy.joints[1].parent, Some(ids[0]));
+        assert_eq!((copy.couplings[0].first, copy.couplings[0].second), (ids[0], ids[1]));
+    }
+
+    #[test]
+    fn hand_built_networks_go_onto_tagged_balls() {
+        let (mut world, balls) = machine();
+        world.tag_ball(balls[2], "bob");
+        let network = Network {
+            links: vec![Link::new("pivot", "bob", 150.0, JointKind::Rope).with_motor(1.0, 10.0).with_break_impulse(500.0)],
+            couplings: Vec::new(),
+        };
+        let ids = network.instantiate(&mut world, &HashMap::new()).unwrap();
+
+        let joint = world.joints.iter().find(|j| j.id == ids[0]).unwrap();
+        assert_eq!((joint.a, joint.b, joint.length), (balls[0], balls[2], 150.0));
+        assert_eq!(joint.break_impulse, Some(500.0));
+    }
+
+    #[test]
+    fn missing_tags_add_nothing() {
+        let (world, balls) = machine();
+        let network = Network::extract(&world, &balls);
+        let mut other = World::new();
+        let err = network.instantiate(&mut other, &HashMap::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(other.joints.is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use raylib::prelude::*;
+
+use crate::world::World;
+
+// floats per ball in `nearest`: present, dx, dy, vx, vy, radius
+pub const BALL_FEATURES: usize = 6;
+// samples per cell side when measuring coverage
+const SUBSAMPLES: usize = 4;
+
+// the `k` balls closest to `origin`, nearest first, as a flat vector of BALL_FEATURES floats each with positions
+// relative to `origin`; `skip` leaves out a ball (usually the one at the origin) and missing balls are all zeros,
+// so the length is always `k * BALL_FEATURES`
+pub fn nearest(world: &World, origin: Vector2, k: usize, skip: Option<usize>) -> Vec<f32> {
+    let mut balls: Vec<_> = world.balls.iter().filter(|b| Some(b.id) != skip).collect();
+    balls.sort_by(|a, b| a.center.distance_to(origin).total_cmp(&b.center.distance_to(origin)).then(a.id.cmp(&b.id)));
+
+    let mut features = vec![0.0; k * BALL_FEATURES];
+    for (slot, ball) in features.chunks_exact_mut(BALL_FEATURES).zip(balls) {
+        let offset = ball.center - origin;
+        slot.copy_from_slice(&[1.0, offset.x, offset.y, ball.velocity.x, ball.velocity.y, ball.radius]);
+    }
+    features
+}
+
+// how much of each cell of a `columns` by `rows` grid over the world's bounds is covered by balls, from 0 to 1,
+// row by row from the bottom left; overlapping balls do not count twice, and across a wrapped side a ball
+// covers the cells on the other side too
+pub fn occupancy(world: &World, columns: usize, rows: usize) -> Vec<f32> {
+    if columns == 0 || rows == 0 {
+        return Vec::new();
+    }
+
+    let bounds = world.bounds;
+    let mut covered = vec![false; columns * rows * SUBSAMPLES * SUBSAMPLES];
+    let (width, height) = (columns * SUBSAMPLES, rows * SUBSAMPLES);
+    let sample = Vector2::new(bounds.width / width as f32, bounds.height / height as f32);
+
+    // only the samples under each ball's bounding box are tested, the box running past a wrapped side
+    let span = |low: f32, high: f32, size: f32, count: usize, periodic: bool| {
+        let (first, last) = ((low / size).floor() as isize, (high / size).floor() as isize);
+        if periodic { first..=last.min(first + count as isize - 1) } else { first.max(0)..=last.min(count as isize - 1) }
+    };
+    for ball in &world.balls {
+        let local = ball.center - Vector2::new(bounds.x, bounds.y);
+        for y in span(local.y - ball.radius, local.y + ball.radius, sample.y, height, world.wrap_y) {
+            for x in span(local.x - ball.radius, local.x + ball.radius, sample.x, width, world.wrap_x) {
+                let point = Vector2::new((x as f32 + 0.5) * sample.x, (y as f32 + 0.5) * sample.y);
+                if point.distance_to(local) <= ball.radius {
+                    covered[y.rem_euclid(height as isize) as usize * width + x.rem_euclid(width as isize) as usize] = true;
+                }
+            }
+        }
+    }
+
+    let mut grid = vec![0.0; columns * rows];
+    for (i, _) in covered.iter().enumerate().filter(|(_, &c)| c) {
+        let (x, y) = (i % width / SUBSAMPLES, i / width / SUBSAMPLES);
+        grid[y * columns + x] += 1.0 / (SUBSAMPLES * SUBSAMPLES) as f32;
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_pads_to_k_balls_nearest_first() {
+        let mut world = World::new();
+        for x in [300.0, 110.0, 100.0] {
+            world.spawn(Vector2::new(x, 100.0), 10.0, Color::RED);
+        }
+        let features = nearest(&world, Vector2::new(100.0, 100.0), 4, Some(2));
+        assert_eq!(features.len(), 4 * BALL_FEATURES);
+        assert_eq!(&features[..3], [1.0, 10.0, 0.0]);
+        assert_eq!(&features[BALL_FEATURES..BALL_FEATURES + 3], [1.0, 200.0, 0.0]);
+        assert!(features[2 * BALL_FEATURES..].iter().all(|&f| f == 0.0));
+    }
+
+    #[test]
+    fn nearest_truncates_to_k_balls() {
+        let mut world = World::new();
+        for x in [100.0, 200.0, 300.0] {
+            world.spawn(Vector2::new(x, 100.0), 10.0, Color::RED);
+        }
+        assert_eq!(nearest(&world, Vector2::zero(), 2, None).len(), 2 * BALL_FEATURES);
+    }
+
+    #[test]
+    fn occupancy_covers_the_grid_row_by_row() {
+        let mut world = World::new();
+        for (x, y) in [(32.0, 24.0), (50.0, 470.0), (700.0, 240.0)] {
+            world.spawn(Vector2::new(x, y), 10.0, Color::RED);
+        }
+        let grid = occupancy(&world, 10, 10);
+        assert_eq!(grid.len(), 100);
+        assert!(grid.iter().all(|&c| (0.0..=1.0).contains(&c)));
+        assert!(grid[0] > 0.0);
+        assert!(grid[90] > 0.0);
+        assert_eq!(grid[55], 0.0);
+        assert!(occupancy(&world, 0, 10).is_empty());
+    }
+
+    #[test]
+    fn occupancy_cells_are_counted_from_the_bounds() {
+        let mut world = World::new();
+        world.bounds = Rectangle::new(100.0, 50.0, 400.0, 200.0);
+        world.spawn(Vector2::new(100.0, 50.0), 30.0, Color::RED);
+        world.spawn(Vector2::new(500.0, 250.0), 30.0, Color::RED);
+
+        let covered = |grid: &[f32]| grid.iter().enumerate().filter(|(_, &c)| c > 0.0).map(|(i, _)| i).collect::<Vec<_>>();
+        assert_eq!(covered(&occupancy(&world, 4, 2)), [0, 7]);
+
+        // wrapped, each corner ball reaches into all four corner cells
+        world.wrap_x = true;
+        world.wrap_y = true;
+        assert_eq!(covered(&occupancy(&world, 4, 2)), [0, 3, 4, 7]);
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::time::{Duration, Instant};
+
+use raylib::prelude::*;
+
+use balls::world::World;
+use balls::{Ball, Camera};
+
+const TRAIL_LENGTH: usize = 90;
+const TRAIL_COLOR: Color = Color::new(0, 120, 220, 255);
+const DRAG_COLOR: Color = Color::new(220, 60, 60, 255);
+// a jump this large between frames means the ball wrapped around, not that it flew
+const TRAIL_BREAK: f32 = 100.0;
+// while the world keeps changing the paths are redone at most this often, each redo costing as many steps as
+// they are long
+const REFRESH: Duration = Duration::from_millis(100);
+
+// paths from World::predict, kept until the world or the aim changes, since running the world ahead costs as
+// much as that many real steps; while the simulation runs or the aim moves they are redone every REFRESH, and
+// right away only when the balls being predicted change
+#[derive(Default)]
+pub struct Prediction {
+    key: Option<u64>,
+    made: Option<Instant>,
+    // the balls the paths are for, the ids and how many were launched
+    of: (Vec<usize>, usize),
+    paths: Vec<Vec<Vector2>>,
+}
+
+impl Prediction {
+    // the paths of the balls `ids` and of `launched`, balls not yet in the world, on a fork with those added
+    pub fn paths(&mut self, world: &World, ids: &[usize], launched: &[Ball], steps: usize, dt: f32) -> &[Vec<Vector2>] {
+        let same_balls = self.of.0 == ids && self.of.1 == launched.len();
+        if same_balls && self.made.is_some_and(|made| made.elapsed() < REFRESH) {
+            return &self.paths;
+        }
+
+        let key = fingerprint(world, ids, launched, steps, dt);
+        if !same_balls || self.key != Some(key) {
+            let mut fork = world.fork();
+            let all: Vec<usize> = ids.iter().copied().chain(launched.iter().map(|&ball| fork.add_ball(ball))).collect();
+            self.paths = fork.predict(&all, steps, dt);
+            self.key = Some(key);
+            self.of = (ids.to_vec(), launched.len());
+            self.made = Some(Instant::now());
+        }
+        &self.paths
+    }
+}
+
+// everything a prediction depends on: all of what `step` reads is saved with the world but the kinematic
+// balls, so the saved form is hashed rather than a list of fields that would miss the next one added
+fn fingerprint(world: &World, ids: &[usize], launched: &[Ball], steps: usize, dt: f32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    // colors move nothing, and a volley being aimed picks new ones every frame
+    let launched: Vec<Ball> = launched.iter().copied().map(|mut ball| {
+        ball.color = Color::BLANK;
+        ball
+    }).collect();
+    serde_json::to_writer(HashWriter(&mut hasher), &(world, &world.kinematic, launched)).expect("a world always serializes");
+    (ids, steps, dt.to_bits()).hash(&mut hasher);
+    hasher.finish()
+}
+
+// feeds whatever is written into the hasher, so nothing is formatted just to be thrown away
+struct HashWriter<'a>(&'a mut DefaultHasher);
+
+impl io::Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// pathlines behind moving balls, plus the field arrows and each ball's drag force
+#[derive(Default)]
+pub struct Streamlines {
+    pub enabled: bool,
+    trails: HashMap<usize, VecDeque<Vector2>>,
+}
+
+impl Streamlines {
+    pub fn clear(&mut self) {
+        self.trails.clear();
+    }
+
+    pub fn record(&mut self, world: &World) {
+        if !self.enabled {
+            return;
+        }
+
+        self.trails.retain(|id, _| world.ball(*id).is_some());
+
+        for ball in world.balls.iter().filter(|b| !b.is_static) {
+            let trail = self.trails.entry(ball.id).or_default();
+
+            if trail.back().is_some_and(|p| p.distance_to(ball.center) > TRAIL_BREAK) {
+                trail.clear();
+            }
+
+            trail.push_back(ball.center);
+            if trail.len() > TRAIL_LENGTH {
+                trail.pop_front();
+            }
+        }
+    }
+
+    pub fn draw(&self, world: &World, cam: &Camera, d: &mut RaylibDrawHandle) {
+        if !self.enabled {
+            return;
+        }
+
+        for field in &world.fields {
+            field.draw(cam, d);
+        }
+
+        for trail in self.trails.values() {
+            for (i, (a, b)) in trail.iter().zip(trail.iter().skip(1)).enumerate() {
+                let alpha = i as f32 / trail.len() as f32;
+                d.draw_line_v(cam.project(*a), cam.project(*b), TRAIL_COLOR.fade(alpha * 0.6));
+            }
+        }
+
+        if world.drag > 0.0 {
+            for ball in world.balls.iter().filter(|b| !b.is_static) {
+                // scaled so the force reads as a length comparable to the ball
+                let force = ball.drag_force(world.drag) / ball.mass * 0.05;
+                d.draw_line_ex(cam.project(ball.center), cam.project(ball.center + force), 2.0, DRAG_COLOR);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use balls::time_zone::TimeZone;
+
+    #[test]
+    fn editing_a_still_world_redoes_the_paths() {
+        let mut world = World::new();
+        let ball = world.spawn(Vector2::new(320.0, 240.0), 10.0, Color::RED);
+        let mut prediction = Prediction::default();
+        let before = prediction.paths(&world, &[ball], &[], 30, 1.0 / 60.0).to_vec();
+
+        world.time_zones.push(TimeZone::new(Rectangle::new(0.0, 0.0, 640.0, 480.0), 0.5));
+        std::thread::sleep(REFRESH);
+        let after = prediction.paths(&world, &[ball], &[], 30, 1.0 / 60.0);
+        assert_ne!(before[0].last(), after[0].last());
+    }
+}
@@ -0,0 +1,24 @@
+// the types most programs built on the simulation need, for a single `use balls::prelude::*`; everything
+// else stays reachable through its module. the enums and structs marked non_exhaustive may gain variants
+// and fields in a minor release, so match them with a wildcard and build them with their constructors.
+// raylib's prelude has a Camera and a Material of its own, so next to it name those two explicitly
+pub use raylib::prelude::{Color, Rectangle, Vector2};
+
+pub use crate::bounce::{BounceLimit, BounceRule};
+pub use crate::coupling::{Coupling, CouplingKind};
+pub use crate::event::Event;
+pub use crate::field::ForceField;
+pub use crate::gym::{Action, Env, Transition};
+pub use crate::joint::{AngleLimit, Joint, JointKind, Motor};
+pub use crate::magnet::Magnet;
+pub use crate::material::Material;
+pub use crate::network::{Link, Network};
+pub use crate::pulse::Pulse;
+pub use crate::scene::Scene;
+pub use crate::settle::Settle;
+pub use crate::terrain::Heightfield;
+pub use crate::thermal::Thermostat;
+pub use crate::thruster::Thruster;
+pub use crate::time_zone::TimeZone;
+pub use crate::world::{minimum_image, World};
+pub use crate::{pick, Ball, Camera};
@@ -0,0 +1,165 @@
+use rand::random;
+use raylib::prelude::*;
+
+use balls::field::ForceField;
+use balls::magnet::MOMENT_PER_AREA;
+use balls::material::Material;
+use balls::pulse::Pulse;
+use balls::terrain::Heightfield;
+use balls::world::World;
+use balls::Ball;
+
+pub struct Preset {
+    pub name: &'static str,
+    pub build: fn() -> World,
+    pub streamlines: bool,
+}
+
+pub const PRESETS: [Preset; 6] = [
+    Preset { name: "balls", build: balls, streamlines: false },
+    Preset { name: "wind tunnel", build: wind_tunnel, streamlines: true },
+    Preset { name: "avalanche", build: avalanche, streamlines: false },
+    Preset { name: "ball pit", build: ball_pit, streamlines: false },
+    Preset { name: "magnets", build: magnets, streamlines: false },
+    Preset { name: "balloon", build: balloon, streamlines: false },
+];
+
+fn rand_between(min: f32, max: f32) -> f32 {
+    min + (max - min) * random::<f32>()
+}
+
+fn random_color() -> Color {
+    Color::new(random(), random(), random(), 255)
+}
+
+pub fn balls() -> World {
+    let mut world = World::new();
+
+    for _ in 0..5 {
+        let radius = rand_between(20.0, 70.0);
+        let center = Vector2::new(rand_between(radius, 640.0 - radius), rand_between(radius, 480.0 - radius));
+
+        world.spawn(center, radius, random_color());
+    }
+
+    world
+}
+
+// light balls blown through obstacles by a steady wind, wrapping back round to the inlet
+pub fn wind_tunnel() -> World {
+    let mut world = World::new();
+    world.drag = 0.01;
+    world.wrap_x = true;
+
+    // the field also carries the balls' weight, like the smoke in a real tunnel, so the net push is horizontal
+    let wind = Vector2::new(1200.0, 0.0) - world.gravity;
+    world.fields.push(ForceField::new(Rectangle::new(0.0, 0.0, 640.0, 480.0), wind));
+
+    let obstacle = Color::new(70, 70, 80, 255);
+    let pin = |world: &mut World, center: Vector2, radius: f32| {
+        world.add_ball(Ball::new(center, radius, obstacle).with_static());
+    };
+
+    // a cylinder, and a tilted plate built from a row of pinned balls
+    pin(&mut world, Vector2::new(260.0, 260.0), 45.0);
+    for i in 0..10 {
+        pin(&mut world, Vector2::new(430.0 + i as f32 * 8.0, 120.0 + i as f32 * 10.0), 7.0);
+    }
+
+    for _ in 0..150 {
+        let center = Vector2::new(rand_between(10.0, 150.0), rand_between(10.0, 470.0));
+        world.add_ball(Ball::new(center, 5.0, random_color()).with_material(Material::WOOD));
+    }
+
+    world
+}
+
+// a heap of sand poured onto the top of a bumpy slope, sliding down until the pile reaches its angle of repose
+pub fn avalanche() -> World {
+    let mut world = World::new();
+
+    let heights = (0..=64)
+        .map(|i| {
+            let x = i as f32 * 10.0;
+            (300.0 - x * 0.45).max(20.0) + (x * 0.11).sin() * 6.0 + rand_between(-2.0, 2.0)
+        })
+        .collect();
+    let terrain = Heightfield::new(0.0, 10.0, heights, Material::SAND.friction).unwrap();
+
+    // stacked in columns from the top of the slope down, spaced so nothing starts out overlapping
+    let mut x = 10.0;
+    while world.balls.len() < 2000 {
+        let mut y = terrain.height_at(x) + 6.0;
+        while y < 470.0 && world.balls.len() < 2000 {
+            let shade = rand_between(150.0, 210.0) as u8;
+            let color = Color::new(shade, shade - 30, 90, 255);
+            world.add_ball(Ball::new(Vector2::new(x, y), rand_between(2.6, 3.2), color).with_material(Material::SAND));
+            y += 6.5;
+        }
+        x += 6.5;
+    }
+
+    world.terrain = Some(terrain);
+    world
+}
+
+// a box filled with bouncy balls, a third of the way up
+pub fn ball_pit() -> World {
+    let mut world = World::new();
+
+    for row in 0..10 {
+        for column in 0..22 {
+            let offset = if row % 2 == 0 { 14.0 } else { 28.0 };
+            pit_ball(&mut world, Vector2::new(offset + column as f32 * 28.0, 16.0 + row as f32 * 26.0));
+        }
+    }
+
+    world
+}
+
+pub fn pit_ball(world: &mut World, center: Vector2) -> usize {
+    world.add_ball(Ball::new(center, rand_between(9.0, 13.0), random_color()).with_material(Material::RUBBER))
+}
+
+// randomly turned dipoles floating without gravity, clumping into chains and rings
+pub fn magnets() -> World {
+    let mut world = World::new();
+    world.gravity = Vector2::zero();
+    world.drag = 0.002;
+
+    for row in 0..8 {
+        for column in 0..12 {
+            let center = Vector2::new(60.0 + column as f32 * 47.0 + rand_between(-8.0, 8.0), 60.0 + row as f32 * 47.0 + rand_between(-8.0, 8.0));
+            let angle = rand_between(0.0, std::f32::consts::TAU);
+            let ball = Ball::new(center, 8.0, random_color()).with_material(Material::STEEL).with_magnet(8.0 * 8.0 * MOMENT_PER_AREA, angle);
+            world.add_ball(ball);
+        }
+    }
+
+    world
+}
+
+// a balloon inflating at the bottom of the ball pit, heaving the balls up, between two pinned balls breathing
+// out of step with each other
+pub fn balloon() -> World {
+    let mut world = World::new();
+
+    for row in 0..6 {
+        for column in 0..22 {
+            let offset = if row % 2 == 0 { 14.0 } else { 28.0 };
+            let center = Vector2::new(offset + column as f32 * 28.0, 50.0 + row as f32 * 26.0);
+            if center.distance_to(Vector2::new(320.0, 40.0)) > 40.0 {
+                pit_ball(&mut world, center);
+            }
+        }
+    }
+
+    let red = Color::new(220, 40, 60, 255);
+    world.add_ball(Ball::new(Vector2::new(320.0, 20.0), 15.0, red).with_material(Material::RUBBER).with_pulse(Pulse::ramp(110.0, 12.0)));
+    for (x, phase) in [(100.0, 0.0), (540.0, 1.5)] {
+        let lung = Ball::new(Vector2::new(x, 380.0), 35.0, Color::DARKBLUE).with_static().with_pulse(Pulse::Breathe { base: 35.0, amplitude: 15.0, period: 3.0, time: phase });
+        world.add_ball(lung);
+    }
+
+    world
+}
@@ -0,0 +1,112 @@
+use std::collections::{HashMap, VecDeque};
+
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Renderer;
+use crate::snapshot::{RectangleDef, Vector2Def};
+use crate::world::World;
+use crate::Camera;
+
+// crossing rates are averaged over this many seconds
+const RATE_WINDOW: f32 = 1.0;
+pub const PROBE_COLOR: Color = Color::new(200, 0, 200, 255);
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum ProbeShape {
+    // counts ball centers crossing the segment, in either direction
+    Line {
+        #[serde(with = "Vector2Def")]
+        from: Vector2,
+        #[serde(with = "Vector2Def")]
+        to: Vector2,
+    },
+    // counts ball centers inside the area
+    Region {
+        #[serde(with = "RectangleDef")]
+        area: Rectangle,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Probe {
+    pub name: String,
+    pub shape: ProbeShape,
+    #[serde(skip)]
+    previous: HashMap<usize, Vector2>,
+    #[serde(skip)]
+    crossings: VecDeque<f32>,
+    #[serde(skip)]
+    time: f32,
+    #[serde(skip)]
+    inside: usize,
+}
+
+impl Probe {
+    pub fn new(name: String, shape: ProbeShape) -> Self {
+        Self { name, shape, previous: HashMap::new(), crossings: VecDeque::new(), time: 0.0, inside: 0 }
+    }
+
+    pub fn record(&mut self, world: &World, dt: f32) {
+        self.time += dt;
+
+        match self.shape {
+            ProbeShape::Line { from, to } => {
+                let bounds = world.bounds;
+                for ball in &world.balls {
+                    let previous = self.previous.insert(ball.id, ball.center);
+                    // a jump across a periodic seam is not a crossing
+                    let crossed = previous.is_some_and(|p| {
+                        let travel = ball.center - p;
+                        travel.x.abs() < bounds.width / 2.0 && travel.y.abs() < bounds.height / 2.0 && intersects(p, ball.center, from, to)
+                    });
+                    if crossed {
+                        self.crossings.push_back(self.time);
+                    }
+                }
+                self.previous.retain(|id, _| world.ball(*id).is_some());
+
+                while self.crossings.front().is_some_and(|&t| t < self.time - RATE_WINDOW) {
+                    self.crossings.pop_front();
+                }
+            }
+            ProbeShape::Region { area } => {
+                self.inside = world.balls.iter()
+                    .filter(|b| b.center.x >= area.x && b.center.x <= area.x + area.width && b.center.y >= area.y && b.center.y <= area.y + area.height)
+                    .count();
+            }
+        }
+    }
+
+    // crossings per second for lines, balls inside for regions
+    pub fn value(&self) -> f32 {
+        match self.shape {
+            ProbeShape::Line { .. } => self.crossings.len() as f32 / RATE_WINDOW.min(self.time.max(f32::EPSILON)),
+            ProbeShape::Region { .. } => self.inside as f32,
+        }
+    }
+
+    pub fn draw(&self, cam: &Camera, d: &mut impl Renderer) {
+        match self.shape {
+            ProbeShape::Line { from, to } => d.line(cam.project(from), cam.project(to), 2.0, PROBE_COLOR),
+            ProbeShape::Region { area } => {
+                let corners = [
+                    Vector2::new(area.x, area.y),
+                    Vector2::new(area.x + area.width, area.y),
+                    Vector2::new(area.x + area.width, area.y + area.height),
+                    Vector2::new(area.x, area.y + area.height),
+                ];
+                for i in 0..4 {
+                    d.line(cam.project(corners[i]), cam.project(corners[(i + 1) % 4]), 2.0, PROBE_COLOR);
+                }
+            }
+        }
+    }
+}
+
+fn intersects(a: Vector2, b: Vector2, c: Vector2, d: Vector2) -> bool {
+    let cross = |o: Vector2, p: Vector2, q: Vector2| (p.x - o.x) * (q.y - o.y) - (p.y - o.y) * (q.x - o.x);
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    (d1 > 0.0) != (d2 > 0.0) && (d3 > 0.0) != (d4 > 0.0)
+}
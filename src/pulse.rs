@@ -0,0 +1,43 @@
+use std::f32::consts::TAU;
+
+use serde::{Deserialize, Serialize};
+
+// a ball's radius changing over time, stepped by the world before anything moves so the broad-phase and the
+// contacts see the new size; the rim's own speed goes into the contacts, so a growing ball shoves what it
+// touches instead of just being pushed apart from it
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Pulse {
+    // grows, or shrinks, by `rate` per second until it reaches `target`, then stops animating
+    Ramp { target: f32, rate: f32 },
+    // swings between `base - amplitude` and `base + amplitude` every `period` seconds, `time` in
+    Breathe { base: f32, amplitude: f32, period: f32, time: f32 },
+}
+
+impl Pulse {
+    pub fn ramp(target: f32, rate: f32) -> Self {
+        Pulse::Ramp { target, rate: rate.abs() }
+    }
+
+    pub fn breathe(base: f32, amplitude: f32, period: f32) -> Self {
+        Pulse::Breathe { base, amplitude, period, time: 0.0 }
+    }
+
+    // the radius `dt` seconds on from `radius`
+    pub fn advance(&mut self, radius: f32, dt: f32) -> f32 {
+        match self {
+            Pulse::Ramp { target, rate } => {
+                let step = *rate * dt;
+                if (*target - radius).abs() <= step { *target } else { radius + step * (*target - radius).signum() }
+            }
+            Pulse::Breathe { base, amplitude, period, time } => {
+                *time = (*time + dt) % period.max(f32::EPSILON);
+                *base + *amplitude * (TAU * *time / period.max(f32::EPSILON)).sin()
+            }
+        }
+    }
+
+    pub fn is_done(&self, radius: f32) -> bool {
+        matches!(self, Pulse::Ramp { target, .. } if *target == radius)
+    }
+}
@@ -0,0 +1,33 @@
+use raylib::prelude::*;
+
+// the drawing the world needs, in screen coordinates; raylib is one backend, the terminal another
+pub trait Renderer {
+    fn circle(&mut self, center: Vector2, radius: f32, color: Color);
+    fn ring(&mut self, center: Vector2, inner: f32, outer: f32, color: Color);
+    fn line(&mut self, from: Vector2, to: Vector2, thickness: f32, color: Color);
+    fn triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color);
+}
+
+impl<T: RaylibDraw> Renderer for T {
+    fn circle(&mut self, center: Vector2, radius: f32, color: Color) {
+        self.draw_circle_v(center, radius, color);
+    }
+
+    fn ring(&mut self, center: Vector2, inner: f32, outer: f32, color: Color) {
+        self.draw_ring(center, inner, outer, 0.0, 360.0, 48, color);
+    }
+
+    fn line(&mut self, from: Vector2, to: Vector2, thickness: f32, color: Color) {
+        if thickness > 1.0 {
+            self.draw_line_ex(from, to, thickness, color);
+        } else {
+            self.draw_line_v(from, to, color);
+        }
+    }
+
+    fn triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color) {
+        // the camera may flip the winding, and raylib culls back faces, so draw both
+        self.draw_triangle(a, b, c, color);
+        self.draw_triangle(a, c, b, color);
+    }
+}
@@ -0,0 +1,32 @@
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::camera_path::Keyframe;
+use crate::probe::Probe;
+use crate::world::World;
+
+// a world plus how to present and measure it; any world snapshot is also a scene without a camera path
+#[derive(Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Scene {
+    #[serde(flatten)]
+    pub world: World,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub camera: Vec<Keyframe>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub probes: Vec<Probe>,
+}
+
+impl Scene {
+    // a scene without a camera path or probes
+    pub fn new(world: World) -> Self {
+        Self { world, camera: Vec::new(), probes: Vec::new() }
+    }
+
+    pub fn load(path: &str) -> io::Result<Scene> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
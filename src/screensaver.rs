@@ -0,0 +1,119 @@
+use std::mem;
+
+use rand::random;
+use raylib::prelude::*;
+
+use balls::world::World;
+use balls::Camera;
+
+use crate::hud::Hud;
+use crate::locale::Locale;
+use crate::presets;
+
+const IDLE_TIMEOUT: f32 = 60.0;
+const SCATTER_RADIUS: f32 = 90.0;
+const SCATTER_STRENGTH: f32 = 6000.0;
+const RESPAWN_INTERVAL: f32 = 0.4;
+// the mouse has to move at least this far in a frame to count as input
+const MOUSE_JITTER: f32 = 2.0;
+
+// a fullscreen ball pit that takes over after IDLE_TIMEOUT without input, or on F10;
+// the cursor pushes balls away and pops the ones it sweeps through, the pit slowly refills,
+// and any key or click hands the previous world back
+#[derive(Default)]
+pub struct Screensaver {
+    idle: f32,
+    respawn: f32,
+    population: usize,
+    last_mouse: Vector2,
+    saved: Option<World>,
+    fullscreen: bool,
+}
+
+impl Screensaver {
+    pub fn is_active(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    // the world the pit is standing in for
+    pub fn saved(&self) -> Option<&World> {
+        self.saved.as_ref()
+    }
+
+    pub fn update(&mut self, rl: &mut RaylibHandle, world: &mut World, cam: &Camera, dt: f32) {
+        let mouse = rl.get_mouse_position();
+        let moved = mouse.distance_to(self.last_mouse) > MOUSE_JITTER;
+        self.last_mouse = mouse;
+
+        let manual = rl.is_key_pressed(KeyboardKey::KEY_F10);
+        let mut pressed = false;
+        while rl.get_key_pressed().is_some() {
+            pressed = true;
+        }
+        pressed |= [MouseButton::MOUSE_LEFT_BUTTON, MouseButton::MOUSE_RIGHT_BUTTON, MouseButton::MOUSE_MIDDLE_BUTTON]
+            .iter()
+            .any(|&b| rl.is_mouse_button_pressed(b));
+
+        if let Some(saved) = self.saved.as_mut() {
+            if pressed {
+                *world = mem::take(saved);
+                self.saved = None;
+                self.idle = 0.0;
+                if self.fullscreen {
+                    rl.toggle_fullscreen();
+                    self.fullscreen = false;
+                }
+                return;
+            }
+
+            scatter(world, cam.unproject(mouse), moved, dt);
+            self.refill(world, dt);
+            return;
+        }
+
+        self.idle = if pressed || moved { 0.0 } else { self.idle + dt };
+        if manual || self.idle > IDLE_TIMEOUT {
+            let pit = presets::ball_pit();
+            self.population = pit.balls.len();
+            self.respawn = 0.0;
+            self.saved = Some(mem::replace(world, pit));
+
+            if !rl.is_window_fullscreen() {
+                rl.toggle_fullscreen();
+                self.fullscreen = true;
+            }
+        }
+    }
+
+    fn refill(&mut self, world: &mut World, dt: f32) {
+        self.respawn -= dt;
+        if self.respawn > 0.0 || world.balls.len() >= self.population {
+            return;
+        }
+
+        self.respawn = RESPAWN_INTERVAL;
+        presets::pit_ball(world, Vector2::new(20.0 + random::<f32>() * 600.0, 460.0));
+    }
+
+    pub fn draw(&self, locale: &Locale, hud: &Hud, d: &mut RaylibDrawHandle) {
+        hud.text(d, locale.text("screensaver.hint"), 10, 460, Color::LIGHTGRAY);
+    }
+}
+
+fn scatter(world: &mut World, point: Vector2, moved: bool, dt: f32) {
+    let mut popped = Vec::new();
+
+    for ball in world.balls.iter_mut().filter(|b| !b.is_static) {
+        let away = ball.center - point;
+        let distance = away.length();
+
+        if moved && distance < ball.radius {
+            popped.push(ball.id);
+        } else if distance < SCATTER_RADIUS && distance > f32::EPSILON {
+            ball.velocity += away / distance * (SCATTER_STRENGTH * (1.0 - distance / SCATTER_RADIUS) * dt);
+            ball.freezing = ball.freezing.max(10);
+        }
+    }
+
+    world.remove_balls(&popped);
+}
@@ -0,0 +1,113 @@
+use raylib::prelude::*;
+
+use balls::{pick, Ball, Camera};
+
+const SELECTION_COLOR: Color = Color::new(255, 200, 0, 255);
+const DRAG_THRESHOLD: f32 = 4.0;
+
+#[derive(Default)]
+pub struct Selection {
+    ids: Vec<usize>,
+    drag_start: Option<Vector2>,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ids(&self) -> &[usize] {
+        &self.ids
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.ids.contains(&id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    // the most recently selected ball, which is what the inspector shows
+    pub fn primary(&self) -> Option<usize> {
+        self.ids.last().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+
+    pub fn remove(&mut self, id: usize) {
+        self.ids.retain(|&i| i != id);
+    }
+
+    fn add(&mut self, id: usize) {
+        if !self.contains(id) {
+            self.ids.push(id);
+        }
+    }
+
+    fn toggle(&mut self, id: usize) {
+        if self.contains(id) {
+            self.ids.retain(|&i| i != id);
+        } else {
+            self.ids.push(id);
+        }
+    }
+
+    pub fn begin_drag(&mut self, screen: Vector2) {
+        self.drag_start = Some(screen);
+    }
+
+    // screen-space rubber band while the drag is far enough to not be a click
+    pub fn drag_rect(&self, screen: Vector2) -> Option<Rectangle> {
+        let start = self.drag_start?;
+        if start.distance_to(screen) < DRAG_THRESHOLD {
+            return None;
+        }
+
+        Some(rect_between(start, screen))
+    }
+
+    pub fn end_drag(&mut self, screen: Vector2, cam: &Camera, balls: &[Ball], additive: bool) {
+        let start = match self.drag_start.take() {
+            Some(start) => start,
+            None => return,
+        };
+
+        if !additive {
+            self.clear();
+        }
+
+        if start.distance_to(screen) < DRAG_THRESHOLD {
+            if let Some(id) = pick(balls, cam.unproject(screen)) {
+                if additive {
+                    self.toggle(id);
+                } else {
+                    self.add(id);
+                }
+            }
+            return;
+        }
+
+        let rect = rect_between(cam.unproject(start), cam.unproject(screen));
+        for ball in balls.iter().filter(|b| rect.check_collision_circle_rec(b.center, b.radius)) {
+            self.add(ball.id);
+        }
+    }
+
+    pub fn draw(&self, cam: &Camera, balls: &[Ball], mouse: Vector2, d: &mut RaylibDrawHandle) {
+        for ball in balls.iter().filter(|b| self.contains(b.id)) {
+            ball.draw_selection(cam, SELECTION_COLOR, d);
+        }
+
+        if let Some(rect) = self.drag_rect(mouse) {
+            d.draw_rectangle_rec(rect, SELECTION_COLOR.fade(0.2));
+            d.draw_rectangle_lines_ex(rect, 1, SELECTION_COLOR);
+        }
+    }
+}
+
+fn rect_between(a: Vector2, b: Vector2) -> Rectangle {
+    Rectangle::new(a.x.min(b.x), a.y.min(b.y), (a.x - b.x).abs(), (a.y - b.y).abs())
+}
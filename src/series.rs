@@ -0,0 +1,74 @@
+use std::fs;
+use std::io;
+
+use balls::probe::Probe;
+use balls::world::World;
+
+const SAMPLE_INTERVAL: f32 = 0.1;
+
+struct Row {
+    time: f32,
+    balls: usize,
+    energy: f32,
+    contacts: usize,
+    probes: Vec<(String, f32)>,
+}
+
+// a time series of the run sampled every SAMPLE_INTERVAL, written out as CSV; probes added midway
+// get their own column, blank before they existed
+#[derive(Default)]
+pub struct Series {
+    time: f32,
+    next_sample: f32,
+    rows: Vec<Row>,
+}
+
+impl Series {
+    pub fn record(&mut self, world: &World, probes: &[Probe], dt: f32) {
+        self.time += dt;
+        if self.time < self.next_sample {
+            return;
+        }
+        self.next_sample = self.time + SAMPLE_INTERVAL;
+
+        self.rows.push(Row {
+            time: self.time,
+            balls: world.balls.len(),
+            energy: world.energy(),
+            contacts: world.contacts,
+            probes: probes.iter().map(|p| (p.name.clone(), p.value())).collect(),
+        });
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut columns: Vec<&str> = Vec::new();
+        for (name, _) in self.rows.iter().flat_map(|r| &r.probes) {
+            if !columns.contains(&name.as_str()) {
+                columns.push(name);
+            }
+        }
+
+        let mut csv = String::from("time,balls,energy,contacts");
+        for column in &columns {
+            csv.push(',');
+            csv.push_str(&column.replace(',', " "));
+        }
+        csv.push('\n');
+
+        for row in &self.rows {
+            csv.push_str(&format!("{:.3},{},{},{}", row.time, row.balls, row.energy, row.contacts));
+            for column in &columns {
+                csv.push(',');
+                if let Some((_, value)) = row.probes.iter().find(|(name, _)| name == column) {
+                    csv.push_str(&value.to_string());
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_csv())
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use raylib::core::input::key_from_i32;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use balls::snapshot::Vector2Def;
+
+use crate::locale::ENGLISH;
+
+const APP: &str = "ball-phys";
+const FILE: &str = "settings.json";
+// the highest raylib key code
+pub const LAST_KEY: i32 = 348;
+
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn toggle(&self) -> Theme {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+
+    pub fn background(&self) -> Color {
+        match self {
+            Theme::Light => Color::WHITE,
+            Theme::Dark => Color::new(24, 24, 28, 255),
+        }
+    }
+
+    pub fn text(&self) -> Color {
+        match self {
+            Theme::Light => Color::DARKGRAY,
+            Theme::Dark => Color::LIGHTGRAY,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LastScene {
+    File(String),
+    Preset(String),
+}
+
+// preferences that outlive a session, kept apart from scenes in the platform's config directory;
+// unknown or missing fields fall back to the defaults so older files keep loading
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window: (i32, i32),
+    pub theme: Theme,
+    // color-blind safe ball palette with patterns, and larger hud text
+    pub accessible: bool,
+    // a locale code like "en" or "es"
+    pub language: String,
+    // keys pressed instead of the defaults, by raylib name, like "KEY_X": "KEY_Z"
+    pub bindings: BTreeMap<String, String>,
+    pub last_scene: Option<LastScene>,
+    #[serde(with = "Vector2Def")]
+    pub camera_position: Vector2,
+    pub camera_scale: f32,
+
+    #[serde(skip)]
+    keys: HashMap<i32, KeyboardKey>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window: (640, 480),
+            theme: Theme::Light,
+            accessible: false,
+            language: ENGLISH.to_string(),
+            bindings: BTreeMap::new(),
+            last_scene: None,
+            camera_position: Vector2::new(0.0, 480.0),
+            camera_scale: 1.0,
+            keys: HashMap::new(),
+        }
+    }
+}
+
+// $XDG_CONFIG_HOME or ~/.config on unix, %APPDATA% on windows and ~/Library/Application Support on macos
+pub fn dir() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).filter(|p| p.is_absolute()).or_else(|| home().map(|h| h.join(".config")))
+    };
+    base.map(|b| b.join(APP))
+}
+
+impl Settings {
+    // the defaults when there is no file yet, or when it cannot be read, after saying why
+    pub fn load() -> Settings {
+        let Some(path) = dir().map(|d| d.join(FILE)) else {
+            return Settings::default();
+        };
+        let mut settings = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+                eprintln!("ignoring {}: {}", path.display(), err);
+                Settings::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Settings::default(),
+            Err(err) => {
+                eprintln!("could not read {}: {}", path.display(), err);
+                Settings::default()
+            }
+        };
+        settings.resolve_bindings();
+        settings
+    }
+
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let dir = dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(FILE);
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    // the key bound in place of `default`, which is itself when it was not rebound
+    pub fn key(&self, default: KeyboardKey) -> KeyboardKey {
+        self.keys.get(&(default as i32)).copied().unwrap_or(default)
+    }
+
+    fn resolve_bindings(&mut self) {
+        let named: HashMap<String, KeyboardKey> = (0..=LAST_KEY).filter_map(key_from_i32).map(|k| (format!("{:?}", k), k)).collect();
+        self.keys.clear();
+        for (from, to) in &self.bindings {
+            match (named.get(from), named.get(to)) {
+                (Some(&from), Some(&to)) => {
+                    self.keys.insert(from as i32, to);
+                }
+                _ => eprintln!("ignoring the binding {} -> {}, expected raylib key names like KEY_X", from, to),
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use serde::Serialize;
+
+use crate::world::World;
+
+// resting contacts keep some jitter in this solver, so a pile at rest still holds some energy: this much is
+// a few dozen balls of radius 10 shivering at a few tens of px/s
+pub const ENERGY: f32 = 2e5;
+pub const STEPS: usize = 60;
+
+// watches the total kinetic energy of the moving balls and reports the first time it stays below `energy`
+// for `steps` steps in a row
+pub struct Settle {
+    pub energy: f32,
+    pub steps: usize,
+    step: usize,
+    time: f32,
+    calm_since: Option<(usize, f32)>,
+    report: Option<Report>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct Report {
+    // when the calm stretch started
+    pub time: f32,
+    pub steps: usize,
+    // top of the highest moving ball, above the ground under it
+    pub pile_height: f32,
+    pub kinetic_energy: f32,
+}
+
+impl Default for Settle {
+    fn default() -> Self {
+        Self::new(ENERGY, STEPS)
+    }
+}
+
+impl Settle {
+    pub fn new(energy: f32, steps: usize) -> Self {
+        Self { energy, steps, step: 0, time: 0.0, calm_since: None, report: None }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.energy, self.steps);
+    }
+
+    pub fn report(&self) -> Option<Report> {
+        self.report
+    }
+
+    // returns the report on the step the world is found settled, None before and after
+    pub fn record(&mut self, world: &World, dt: f32) -> Option<Report> {
+        self.step += 1;
+        self.time += dt;
+        if self.report.is_some() {
+            return None;
+        }
+
+        let mut moving = world.balls.iter().filter(|b| !b.is_static).peekable();
+        let any = moving.peek().is_some();
+        let energy: f32 = moving.map(|b| 0.5 * b.mass * b.velocity.length_sqr()).sum();
+        if !any || energy > self.energy {
+            self.calm_since = None;
+            return None;
+        }
+
+        let (step, time) = *self.calm_since.get_or_insert((self.step, self.time));
+        if self.step - step + 1 < self.steps {
+            return None;
+        }
+
+        let ground = |x: f32| world.terrain.as_ref().map_or(world.bounds.y, |t| t.height_at(x));
+        let pile_height = world.balls.iter().filter(|b| !b.is_static).map(|b| b.center.y + b.radius - ground(b.center.x)).fold(0.0, f32::max);
+        self.report = Some(Report { time, steps: step, pile_height, kinetic_energy: energy });
+        self.report
+    }
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::io;
+
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::world::World;
+
+// serde mirrors of the raylib types the world stores, raylib's own serde feature does not build
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Vector2")]
+pub struct Vector2Def {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Color")]
+pub struct ColorDef {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Rectangle")]
+pub struct RectangleDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+// writes next to the target first, so a crash mid-write never leaves a torn file behind
+pub fn save(world: &World, path: &str) -> io::Result<()> {
+    let temp = format!("{}.tmp", path);
+    fs::write(&temp, serde_json::to_string(world)?)?;
+    fs::rename(temp, path)
+}
+
+pub fn load(path: &str) -> io::Result<World> {
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use balls::world::World;
+
+// per-run performance and conservation numbers, written out as JSON for tracking across commits
+#[derive(Default)]
+pub struct Stats {
+    step_times: Vec<f32>,
+    max_bodies: usize,
+    collisions: usize,
+    initial_energy: Option<f32>,
+    energy: f32,
+}
+
+#[derive(Serialize)]
+pub struct Summary {
+    pub version: &'static str,
+    pub steps: usize,
+    pub step_time_ms: StepTimes,
+    pub max_bodies: usize,
+    pub total_collisions: usize,
+    pub energy: Energy,
+}
+
+#[derive(Serialize)]
+pub struct StepTimes {
+    pub mean: f32,
+    pub p50: f32,
+    pub p95: f32,
+    pub p99: f32,
+    pub max: f32,
+}
+
+#[derive(Serialize)]
+pub struct Energy {
+    pub initial: f32,
+    pub last: f32,
+    // relative change since the world was loaded
+    pub drift: f32,
+}
+
+impl Stats {
+    // call after every step with the time the step took
+    pub fn record(&mut self, world: &World, step_time: Duration) {
+        self.step_times.push(step_time.as_secs_f32() * 1000.0);
+        self.max_bodies = self.max_bodies.max(world.balls.len());
+        self.collisions += world.new_contacts;
+
+        self.energy = world.energy();
+        self.initial_energy.get_or_insert(self.energy);
+    }
+
+    // a freshly loaded world starts its own energy baseline
+    pub fn reset_energy(&mut self) {
+        self.initial_energy = None;
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut sorted = self.step_times.clone();
+        sorted.sort_by(f32::total_cmp);
+
+        let percentile = |p: f32| {
+            let i = ((sorted.len() as f32 - 1.0) * p).round() as usize;
+            sorted.get(i).copied().unwrap_or(0.0)
+        };
+        let initial = self.initial_energy.unwrap_or(0.0);
+
+        Summary {
+            version: env!("CARGO_PKG_VERSION"),
+            steps: sorted.len(),
+            step_time_ms: StepTimes {
+                mean: sorted.iter().sum::<f32>() / sorted.len().max(1) as f32,
+                p50: percentile(0.5),
+                p95: percentile(0.95),
+                p99: percentile(0.99),
+                max: sorted.last().copied().unwrap_or(0.0),
+            },
+            max_bodies: self.max_bodies,
+            total_collisions: self.collisions,
+            energy: Energy {
+                initial,
+                last: self.energy,
+                drift: if initial != 0.0 { (self.energy - initial) / initial.abs() } else { 0.0 },
+            },
+        }
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.summary())?;
+        fs::write(path, json)
+    }
+}
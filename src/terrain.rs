@@ -0,0 +1,150 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Renderer;
+use crate::{Ball, Camera};
+
+const TERRAIN_COLOR: Color = Color::new(140, 120, 90, 255);
+const OUTLINE_COLOR: Color = Color::new(90, 75, 55, 255);
+
+// a static ground surface sampled every `spacing` units starting at `left`, at least two samples long; the
+// samples are fixed once made so they stay valid
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "Samples")]
+#[non_exhaustive]
+pub struct Heightfield {
+    left: f32,
+    spacing: f32,
+    heights: Vec<f32>,
+    pub friction: f32,
+}
+
+// a heightfield as scene files hold it, checked before it is used
+#[derive(Deserialize)]
+struct Samples {
+    left: f32,
+    spacing: f32,
+    heights: Vec<f32>,
+    friction: f32,
+}
+
+impl TryFrom<Samples> for Heightfield {
+    type Error = String;
+
+    fn try_from(samples: Samples) -> Result<Self, String> {
+        Self::new(samples.left, samples.spacing, samples.heights, samples.friction)
+    }
+}
+
+impl Heightfield {
+    pub fn new(left: f32, spacing: f32, heights: Vec<f32>, friction: f32) -> Result<Self, String> {
+        if heights.len() < 2 {
+            return Err(format!("a heightfield needs at least 2 heights, got {}", heights.len()));
+        }
+        if !spacing.is_finite() || spacing <= 0.0 {
+            return Err(format!("a heightfield's spacing must be positive, got {}", spacing));
+        }
+        Ok(Self { left, spacing, heights, friction })
+    }
+
+    pub fn left(&self) -> f32 {
+        self.left
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    pub fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
+    fn point(&self, i: usize) -> Vector2 {
+        Vector2::new(self.left + self.spacing * i as f32, self.heights[i])
+    }
+
+    pub fn height_at(&self, x: f32) -> f32 {
+        let last = self.heights.len() - 1;
+        let t = ((x - self.left) / self.spacing).clamp(0.0, last as f32);
+        let i = (t as usize).min(last - 1);
+        let (a, b) = (self.heights[i], self.heights[i + 1]);
+
+        a + (b - a) * (t - i as f32)
+    }
+
+    pub fn collide(&self, ball: &mut Ball) {
+        // a center that sank below the surface is lifted back on top first, the contact below pushes it clear
+        let surface = self.height_at(ball.center.x);
+        if ball.center.y < surface {
+            ball.center.y = surface + 0.01;
+        }
+
+        let first = ((ball.center.x - ball.radius - self.left) / self.spacing).floor().max(0.0) as usize;
+        let last = (((ball.center.x + ball.radius - self.left) / self.spacing).ceil().max(0.0) as usize).min(self.heights.len() - 1);
+
+        for i in first..last {
+            let (start, end) = (self.point(i), self.point(i + 1));
+            let along = end - start;
+            let t = ((ball.center - start).dot(along) / along.length_sqr()).clamp(0.0, 1.0);
+            let offset = ball.center - (start + along * t);
+            let distance = offset.length();
+            if distance >= ball.radius || distance <= f32::EPSILON {
+                continue;
+            }
+
+            let normal = offset / distance;
+            ball.center += normal * (ball.radius - distance);
+
+            let approach = ball.velocity.dot(normal);
+            if approach >= 0.0 {
+                continue;
+            }
+
+            let sliding = ball.velocity - normal * approach;
+            let speed = sliding.length();
+            let friction = (ball.material.friction * self.friction).sqrt() * (1.0 + ball.material.restitution) * -approach;
+
+            ball.velocity = normal * (-approach * ball.material.restitution);
+            if speed > friction {
+                ball.velocity += sliding * (1.0 - friction / speed);
+            }
+        }
+    }
+
+    pub fn draw(&self, cam: &Camera, d: &mut impl Renderer) {
+        for i in 0..self.heights.len() - 1 {
+            let (start, end) = (self.point(i), self.point(i + 1));
+            let (top_left, top_right) = (cam.project(start), cam.project(end));
+            let bottom_left = cam.project(Vector2::new(start.x, 0.0));
+            let bottom_right = cam.project(Vector2::new(end.x, 0.0));
+
+            d.triangle(top_left, bottom_left, bottom_right, TERRAIN_COLOR);
+            d.triangle(top_left, bottom_right, top_right, TERRAIN_COLOR);
+            d.line(top_left, top_right, 2.0, OUTLINE_COLOR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_files_without_enough_heights_are_rejected() {
+        for json in [r#"{"left":0,"spacing":10,"heights":[],"friction":0.5}"#, r#"{"left":0,"spacing":10,"heights":[5],"friction":0.5}"#] {
+            assert!(serde_json::from_str::<Heightfield>(json).is_err());
+        }
+        assert!(serde_json::from_str::<Heightfield>(r#"{"left":0,"spacing":0,"heights":[1,2],"friction":0.5}"#).is_err());
+        assert!(serde_json::from_str::<Heightfield>(r#"{"left":0,"spacing":10,"heights":[1,2],"friction":0.5}"#).is_ok());
+        assert!(Heightfield::new(0.0, 10.0, vec![5.0], 0.5).is_err());
+    }
+
+    #[test]
+    fn height_is_interpolated_and_clamped_at_the_ends() {
+        let terrain = Heightfield::new(0.0, 10.0, vec![0.0, 10.0, 30.0], 0.5).unwrap();
+        assert_eq!(terrain.height_at(5.0), 5.0);
+        assert_eq!(terrain.height_at(15.0), 20.0);
+        assert_eq!(terrain.height_at(-50.0), 0.0);
+        assert_eq!(terrain.height_at(500.0), 30.0);
+    }
+}
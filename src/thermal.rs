@@ -0,0 +1,63 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Ball;
+
+const DAMPING: f32 = 1.0;
+const SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// a langevin heat bath: every ball is slowed by `damping` (per second) and kicked by random forces
+// balanced against it, so speeds relax towards `temperature` (in mass * px^2 / s^2) whatever the step;
+// the noise comes from its own generator so snapshots replay the same
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Thermostat {
+    pub temperature: f32,
+    pub damping: f32,
+    state: u64,
+}
+
+impl Default for Thermostat {
+    fn default() -> Self {
+        Self { temperature: 0.0, damping: DAMPING, state: SEED }
+    }
+}
+
+impl Thermostat {
+    pub fn is_on(&self) -> bool {
+        self.temperature > 0.0 && self.damping > 0.0
+    }
+
+    // the exact solution of the velocity's ornstein-uhlenbeck process over `dt`
+    pub fn apply(&mut self, ball: &mut Ball, dt: f32) {
+        let decay = (-self.damping * dt).exp();
+        let spread = (self.temperature / ball.mass * (1.0 - decay * decay)).sqrt();
+
+        let noise = Vector2::new(self.gaussian(), self.gaussian());
+        ball.velocity = ball.velocity * decay + noise * spread;
+        ball.freezing = ball.freezing.max(10);
+    }
+
+    // box-muller over xorshift
+    fn gaussian(&mut self) -> f32 {
+        let u = self.uniform().max(f32::MIN_POSITIVE);
+        let v = self.uniform();
+        (-2.0 * u.ln()).sqrt() * (std::f32::consts::TAU * v).cos()
+    }
+
+    fn uniform(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+// the temperature the moving balls actually have, two degrees of freedom each
+pub fn measure(balls: &[Ball]) -> f32 {
+    let moving: Vec<&Ball> = balls.iter().filter(|b| !b.is_static).collect();
+    if moving.is_empty() {
+        return 0.0;
+    }
+
+    moving.iter().map(|b| b.mass * b.velocity.length_sqr()).sum::<f32>() / (2.0 * moving.len() as f32)
+}
@@ -0,0 +1,104 @@
+use rand::Rng;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Renderer;
+use crate::world::World;
+use crate::Camera;
+
+const EXHAUST_RATE: f32 = 150.0;
+const EXHAUST_LIFE: f32 = 0.5;
+const EXHAUST_SPEED: f32 = 250.0;
+const EXHAUST_SPREAD: f32 = 0.25;
+const EXHAUST_COLOR: Color = Color::new(255, 140, 30, 255);
+
+// a continuous force on a ball along `angle` (radians from the world x axis), scaled by `throttle` in 0..1
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Thruster {
+    pub ball: usize,
+    pub angle: f32,
+    pub force: f32,
+    pub throttle: f32,
+}
+
+impl Thruster {
+    pub fn new(ball: usize, angle: f32, force: f32) -> Self {
+        Self { ball, angle, force, throttle: 0.0 }
+    }
+
+    pub fn direction(&self) -> Vector2 {
+        Vector2::new(self.angle.cos(), self.angle.sin())
+    }
+
+    pub fn thrust(&self) -> Vector2 {
+        self.direction() * self.force * self.throttle.clamp(0.0, 1.0)
+    }
+}
+
+struct Particle {
+    position: Vector2,
+    velocity: Vector2,
+    life: f32,
+}
+
+// purely visual, the exhaust does not push anything
+#[derive(Default)]
+pub struct Exhaust {
+    particles: Vec<Particle>,
+    pending: f32,
+}
+
+impl Exhaust {
+    pub fn update(&mut self, world: &World, dt: f32) {
+        for particle in &mut self.particles {
+            particle.position += particle.velocity * dt;
+            particle.life -= dt;
+        }
+        self.particles.retain(|p| p.life > 0.0);
+
+        self.pending += EXHAUST_RATE * dt;
+        let count = self.pending as usize;
+        self.pending -= count as f32;
+
+        let mut rng = rand::thread_rng();
+        for thruster in world.thrusters.iter().filter(|t| t.throttle > 0.0) {
+            let Some(ball) = world.ball(thruster.ball) else {
+                continue;
+            };
+
+            let back = -thruster.direction();
+            for _ in 0..(count as f32 * thruster.throttle.min(1.0)).ceil() as usize {
+                let spread = rng.gen_range(-EXHAUST_SPREAD..EXHAUST_SPREAD);
+                let (sin, cos) = spread.sin_cos();
+                let direction = Vector2::new(back.x * cos - back.y * sin, back.x * sin + back.y * cos);
+
+                self.particles.push(Particle {
+                    position: ball.center + back * ball.radius,
+                    velocity: ball.velocity + direction * EXHAUST_SPEED * rng.gen_range(0.6..1.0),
+                    life: EXHAUST_LIFE * rng.gen_range(0.5..1.0),
+                });
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn draw(&self, world: &World, cam: &Camera, d: &mut impl Renderer) {
+        for particle in &self.particles {
+            let fade = particle.life / EXHAUST_LIFE;
+            let color = Color::new(EXHAUST_COLOR.r, (EXHAUST_COLOR.g as f32 * fade) as u8, EXHAUST_COLOR.b, (255.0 * fade) as u8);
+            d.circle(cam.project(particle.position), cam.scale(2.0 + 2.0 * (1.0 - fade)), color);
+        }
+
+        // the nozzle, pointing against the thrust
+        for thruster in &world.thrusters {
+            if let Some(ball) = world.ball(thruster.ball) {
+                let back = ball.center - thruster.direction() * ball.radius;
+                d.line(cam.project(back), cam.project(back - thruster.direction() * ball.radius * 0.5), 3.0, Color::DARKGRAY);
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::f32::consts::FRAC_PI_2;
+
+use raylib::prelude::*;
+
+use balls::joint::rotate;
+use balls::world::World;
+
+// standard gravity in m/s^2, what a device at rest reports along the downward axis
+const EARTH: f32 = 9.81;
+
+// turns the world's own gravity with the device, taken from its accelerometer: held upright the world keeps
+// the gravity it was made with, and laid flat it loses it. when the sensor stops reporting the world gets its
+// gravity back, and a world whose gravity was set from elsewhere meanwhile is turned from that instead
+#[derive(Default)]
+pub struct Tilt {
+    base: Vector2,
+    // what was last written to the world, None while the world's gravity is its own
+    applied: Option<Vector2>,
+}
+
+impl Tilt {
+    pub fn update(&mut self, world: &mut World) {
+        self.apply(world, platform::acceleration());
+    }
+
+    fn apply(&mut self, world: &mut World, reading: Option<(f32, f32)>) {
+        if self.applied.is_some_and(|applied| applied != world.gravity) {
+            self.applied = None;
+        }
+        match reading {
+            Some(reading) => {
+                if self.applied.is_none() {
+                    self.base = world.gravity;
+                }
+                world.gravity = tilted(self.base, reading);
+                self.applied = Some(world.gravity);
+            }
+            None => {
+                if self.applied.take().is_some() {
+                    world.gravity = self.base;
+                }
+            }
+        }
+    }
+}
+
+// the sensor reports the reaction to gravity, with x to the right and y up the screen
+fn tilted(base: Vector2, (x, y): (f32, f32)) -> Vector2 {
+    let down = Vector2::new(-x, -y);
+    rotate(base, down.y.atan2(down.x) + FRAC_PI_2) * (down.length() / EARTH)
+}
+
+// browsers expose devicemotion through emscripten's html5 api
+#[cfg(target_os = "emscripten")]
+mod platform {
+    use std::os::raw::{c_int, c_void};
+    use std::sync::{Mutex, Once};
+
+    // low-pass factor for the raw readings, the sensor is noisy
+    const SMOOTHING: f32 = 0.2;
+    const SUPPORTS_ACCELERATION_INCLUDING_GRAVITY: c_int = 0x02;
+    const CALLING_THREAD: usize = 0x2;
+
+    #[repr(C)]
+    struct DeviceMotionEvent {
+        acceleration_x: f64,
+        acceleration_y: f64,
+        acceleration_z: f64,
+        acceleration_including_gravity_x: f64,
+        acceleration_including_gravity_y: f64,
+        acceleration_including_gravity_z: f64,
+        rotation_rate_alpha: f64,
+        rotation_rate_beta: f64,
+        rotation_rate_gamma: f64,
+        supported_fields: c_int,
+    }
+
+    type Callback = extern "C" fn(c_int, *const DeviceMotionEvent, *mut c_void) -> c_int;
+
+    extern "C" {
+        fn emscripten_set_devicemotion_callback_on_thread(user_data: *mut c_void, use_capture: c_int, callback: Callback, thread: usize) -> c_int;
+    }
+
+    static LISTEN: Once = Once::new();
+    static LATEST: Mutex<Option<(f32, f32)>> = Mutex::new(None);
+
+    extern "C" fn on_motion(_: c_int, event: *const DeviceMotionEvent, _: *mut c_void) -> c_int {
+        let Some(event) = (unsafe { event.as_ref() }) else {
+            return 0;
+        };
+        if event.supported_fields & SUPPORTS_ACCELERATION_INCLUDING_GRAVITY == 0 {
+            return 0;
+        }
+
+        let reading = (event.acceleration_including_gravity_x as f32, event.acceleration_including_gravity_y as f32);
+        let mut latest = LATEST.lock().unwrap();
+        *latest = Some(match *latest {
+            Some((x, y)) => (x + (reading.0 - x) * SMOOTHING, y + (reading.1 - y) * SMOOTHING),
+            None => reading,
+        });
+        0
+    }
+
+    pub fn acceleration() -> Option<(f32, f32)> {
+        LISTEN.call_once(|| unsafe {
+            emscripten_set_devicemotion_callback_on_thread(std::ptr::null_mut(), 0, on_motion, CALLING_THREAD);
+        });
+
+        *LATEST.lock().unwrap()
+    }
+}
+
+#[cfg(not(target_os = "emscripten"))]
+mod platform {
+    pub fn acceleration() -> Option<(f32, f32)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPRIGHT: (f32, f32) = (0.0, EARTH);
+
+    #[test]
+    fn upright_keeps_the_worlds_gravity_and_sideways_turns_it() {
+        let base = Vector2::new(0.0, -500.0);
+        assert!((tilted(base, UPRIGHT) - base).length() < 1e-3);
+        // the right edge down
+        assert!((tilted(base, (-EARTH, 0.0)) - Vector2::new(500.0, 0.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn zero_gravity_stays_zero() {
+        let mut world = World::new();
+        world.gravity = Vector2::zero();
+        Tilt::default().apply(&mut world, Some((-EARTH, 0.0)));
+        assert_eq!(world.gravity, Vector2::zero());
+    }
+
+    #[test]
+    fn the_worlds_gravity_comes_back_when_the_sensor_stops() {
+        let mut world = World::new();
+        let own = Vector2::new(0.0, -200.0);
+        world.gravity = own;
+        let mut tilt = Tilt::default();
+        tilt.apply(&mut world, Some((-EARTH, 0.0)));
+        tilt.apply(&mut world, Some((-EARTH, 0.0)));
+        assert!((world.gravity - Vector2::new(200.0, 0.0)).length() < 1e-3);
+        tilt.apply(&mut world, None);
+        assert_eq!(world.gravity, own);
+    }
+
+    #[test]
+    fn a_new_worlds_gravity_is_turned_instead() {
+        let mut world = World::new();
+        let mut tilt = Tilt::default();
+        tilt.apply(&mut world, Some(UPRIGHT));
+        world.gravity = Vector2::new(0.0, -100.0);
+        tilt.apply(&mut world, Some((-EARTH, 0.0)));
+        assert!((world.gravity - Vector2::new(100.0, 0.0)).length() < 1e-3);
+        tilt.apply(&mut world, None);
+        assert_eq!(world.gravity, Vector2::new(0.0, -100.0));
+    }
+}
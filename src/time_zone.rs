@@ -0,0 +1,72 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Renderer;
+use crate::snapshot::RectangleDef;
+use crate::Camera;
+
+// the scale eases in over this far inside the edge, so a ball crossing it slows down over a few steps
+// instead of all at once
+const FEATHER: f32 = 20.0;
+// a zone never quite stops time, balls in it would otherwise never leave
+const MIN_SCALE: f32 = 0.05;
+const MAX_SCALE: f32 = 2.0;
+const COLOR: Color = Color::new(150, 90, 255, 255);
+
+// a region where time runs at `scale`: balls whose center is inside move, accelerate and animate by that
+// fraction of each step. velocities are kept in the ball's own time, so a ball leaves a bubble as fast as
+// it came in, and collisions across the edge exchange momentum as usual
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TimeZone {
+    #[serde(with = "RectangleDef")]
+    pub area: Rectangle,
+    pub scale: f32,
+}
+
+impl TimeZone {
+    pub fn new(area: Rectangle, scale: f32) -> Self {
+        Self { area, scale: scale.clamp(MIN_SCALE, MAX_SCALE) }
+    }
+
+    pub fn contains(&self, point: Vector2) -> bool {
+        self.depth(point) >= 0.0
+    }
+
+    // how far inside the nearest edge the point is, negative outside
+    fn depth(&self, point: Vector2) -> f32 {
+        let a = self.area;
+        (point.x - a.x).min(a.x + a.width - point.x).min(point.y - a.y).min(a.y + a.height - point.y)
+    }
+
+    // 1 outside, `scale` past the feathered edge and smoothly in between
+    pub fn scale_at(&self, point: Vector2) -> f32 {
+        let t = (self.depth(point) / FEATHER).clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        1.0 + (self.scale - 1.0) * eased
+    }
+
+    pub fn draw(&self, cam: &Camera, d: &mut impl Renderer) {
+        let a = self.area;
+        let corners = [
+            Vector2::new(a.x, a.y),
+            Vector2::new(a.x + a.width, a.y),
+            Vector2::new(a.x + a.width, a.y + a.height),
+            Vector2::new(a.x, a.y + a.height),
+        ]
+        .map(|c| cam.project(c));
+
+        // slow zones are tinted deeper the slower they run
+        let tint = COLOR.fade(0.08 + 0.12 * (1.0 - self.scale).abs().min(1.0));
+        d.triangle(corners[0], corners[2], corners[1], tint);
+        d.triangle(corners[0], corners[3], corners[2], tint);
+        for i in 0..4 {
+            d.line(corners[i], corners[(i + 1) % 4], 1.0, COLOR.fade(0.6));
+        }
+    }
+}
+
+// how fast time runs at `point`, overlapping zones compounding
+pub fn scale_at(zones: &[TimeZone], point: Vector2) -> f32 {
+    zones.iter().map(|z| z.scale_at(point)).product()
+}
@@ -0,0 +1,211 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::style::{self, Color as TermColor};
+use crossterm::{cursor, queue, terminal};
+use raylib::prelude::*;
+
+use balls::render::Renderer;
+use balls::Camera;
+
+use crate::headless;
+use crate::locale::Locale;
+use crate::settings::Settings;
+use crate::{arg, Clock};
+
+const SCREEN: Vector2 = Vector2::new(640.0, 480.0);
+const FPS: f32 = 30.0;
+// physics keeps its usual step, several of them per drawn frame
+const SUBSTEPS: usize = 4;
+
+// draws into a grid of terminal cells, each covering a patch of the 640x480 screen
+pub struct TerminalRenderer {
+    columns: usize,
+    rows: usize,
+    cell: Vector2,
+    cells: Vec<(char, Color)>,
+}
+
+impl TerminalRenderer {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        Self {
+            columns,
+            rows,
+            cell: Vector2::new(SCREEN.x / columns as f32, SCREEN.y / rows as f32),
+            cells: vec![(' ', Color::WHITE); columns * rows],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill((' ', Color::WHITE));
+    }
+
+    fn center_of(&self, column: usize, row: usize) -> Vector2 {
+        Vector2::new((column as f32 + 0.5) * self.cell.x, (row as f32 + 0.5) * self.cell.y)
+    }
+
+    fn plot(&mut self, point: Vector2, glyph: char, color: Color) {
+        let (column, row) = (point.x / self.cell.x, point.y / self.cell.y);
+        if column >= 0.0 && row >= 0.0 && (column as usize) < self.columns && (row as usize) < self.rows {
+            self.cells[row as usize * self.columns + column as usize] = (glyph, color);
+        }
+    }
+
+    // every cell whose center is within the screen rectangle spanned by `min` and `max`
+    fn cells_within(&self, min: Vector2, max: Vector2) -> impl Iterator<Item = (Vector2, usize)> + '_ {
+        let first_column = (min.x / self.cell.x).floor().max(0.0) as usize;
+        let first_row = (min.y / self.cell.y).floor().max(0.0) as usize;
+        let last_column = ((max.x / self.cell.x).ceil().max(0.0) as usize).min(self.columns);
+        let last_row = ((max.y / self.cell.y).ceil().max(0.0) as usize).min(self.rows);
+
+        (first_row..last_row).flat_map(move |row| {
+            (first_column..last_column).map(move |column| (self.center_of(column, row), row * self.columns + column))
+        })
+    }
+
+    pub fn flush(&self, out: &mut impl Write, status: &str) -> io::Result<()> {
+        for row in 0..self.rows {
+            queue!(out, cursor::MoveTo(0, row as u16))?;
+
+            let mut current = None;
+            for &(glyph, color) in &self.cells[row * self.columns..(row + 1) * self.columns] {
+                if current != Some(color) {
+                    queue!(out, style::SetForegroundColor(TermColor::Rgb { r: color.r, g: color.g, b: color.b }))?;
+                    current = Some(color);
+                }
+                queue!(out, style::Print(glyph))?;
+            }
+        }
+
+        queue!(out, cursor::MoveTo(0, self.rows as u16), style::ResetColor, terminal::Clear(terminal::ClearType::CurrentLine), style::Print(status))?;
+        out.flush()
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn circle(&mut self, center: Vector2, radius: f32, color: Color) {
+        let reach = Vector2::one() * radius;
+        let inside: Vec<usize> = self.cells_within(center - reach, center + reach)
+            .filter(|(middle, _)| middle.distance_to(center) <= radius)
+            .map(|(_, index)| index)
+            .collect();
+
+        // balls smaller than a cell still take up the one they are in
+        if inside.is_empty() {
+            self.plot(center, 'o', color);
+        }
+        for index in inside {
+            self.cells[index] = ('O', color);
+        }
+    }
+
+    fn ring(&mut self, center: Vector2, inner: f32, outer: f32, color: Color) {
+        // a few pixels of ring are thinner than a cell, so widen it inwards by half a cell
+        let inner = inner - self.cell.x.max(self.cell.y) / 2.0;
+        let reach = Vector2::one() * outer;
+        let edge: Vec<usize> = self.cells_within(center - reach, center + reach)
+            .filter(|(middle, _)| (inner..=outer).contains(&middle.distance_to(center)))
+            .map(|(_, index)| index)
+            .collect();
+
+        for index in edge {
+            self.cells[index] = ('#', color);
+        }
+    }
+
+    fn line(&mut self, from: Vector2, to: Vector2, thickness: f32, color: Color) {
+        let glyph = if thickness > 1.0 { '*' } else { '.' };
+        let steps = (from.distance_to(to) / (self.cell.x.min(self.cell.y) / 2.0)).ceil().max(1.0) as usize;
+
+        for i in 0..=steps {
+            self.plot(from.lerp(to, i as f32 / steps as f32), glyph, color);
+        }
+    }
+
+    fn triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color) {
+        let min = Vector2::new(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y));
+        let max = Vector2::new(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y));
+        let side = |p: Vector2, q: Vector2, r: Vector2| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+
+        let inside: Vec<usize> = self.cells_within(min, max)
+            .filter(|&(m, _)| {
+                let (ab, bc, ca) = (side(a, b, m), side(b, c, m), side(c, a, m));
+                (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+            })
+            .map(|(_, index)| index)
+            .collect();
+
+        for index in inside {
+            self.cells[index] = (':', color);
+        }
+    }
+}
+
+// puts the terminal back however the loop ends
+struct RawMode;
+
+impl RawMode {
+    fn enter(out: &mut impl Write) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        queue!(out, terminal::EnterAlternateScreen, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+        out.flush()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = queue!(out, style::ResetColor, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// runs a preset in the terminal, `--preset <name>` picks it; q or escape quits
+pub fn run(args: &[String]) -> io::Result<()> {
+    let Some(preset) = headless::preset(args) else {
+        return Ok(());
+    };
+
+    let language = arg(args, "--lang").map_or_else(|| Settings::load().language, str::to_string);
+    let locale = Locale::load(&language);
+
+    let mut out = io::stdout();
+    let _raw = RawMode::enter(&mut out)?;
+
+    let cam = Camera::new(Vector2::new(0.0, SCREEN.y), 1.0).invert_v();
+    let mut world = (preset.build)();
+    let mut clock = Clock::new(Clock::frame_cap(FPS));
+    let (columns, rows) = terminal::size()?;
+    let mut renderer = TerminalRenderer::new(columns as usize, (rows as usize).saturating_sub(1).max(1));
+
+    loop {
+        while event::poll(Duration::ZERO)? {
+            match event::read()? {
+                Event::Key(key) if key.code == KeyCode::Char('q') || key.code == KeyCode::Esc => return Ok(()),
+                Event::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                Event::Resize(columns, rows) => {
+                    queue!(out, terminal::Clear(terminal::ClearType::All))?;
+                    renderer = TerminalRenderer::new(columns as usize, (rows as usize).saturating_sub(1).max(1));
+                }
+                _ => {}
+            }
+        }
+
+        let dt = clock.tick();
+        for _ in 0..SUBSTEPS {
+            world.step(1.0 / (FPS * SUBSTEPS as f32));
+        }
+        world.events.clear();
+
+        renderer.clear();
+        world.draw(&cam, false, &mut renderer);
+
+        let name = locale.text(&format!("preset.{}", preset.name)).to_string();
+        let status = locale.format("tui.status", &[&name, &world.balls.len(), &format!("{:.0}", 1.0 / dt.max(f32::EPSILON))]);
+        renderer.flush(&mut out, &status)?;
+    }
+}
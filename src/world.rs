@@ -0,0 +1,890 @@
+use std::collections::{BTreeMap, HashSet};
+
+use raylib::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bounce::{BounceLimit, BounceRule};
+use crate::broadphase::Grid;
+use crate::coupling::{Coupling, CouplingKind};
+use crate::event::Event;
+use crate::field::ForceField;
+use crate::joint::{Hinge, Joint};
+use crate::magnet;
+use crate::render::Renderer;
+use crate::terrain::Heightfield;
+use crate::thermal::Thermostat;
+use crate::thruster::Thruster;
+use crate::time_zone::{self, TimeZone};
+use crate::snapshot::{RectangleDef, Vector2Def};
+use crate::{Ball, Camera};
+
+const JOINT_ITERATIONS: usize = 4;
+const COLLISION_ITERATIONS: usize = 3;
+const BOUNDS: Rectangle = Rectangle { x: 0.0, y: 0.0, width: 640.0, height: 480.0 };
+const MIN_RADIUS: f32 = 2.0;
+const GRAVITY: Vector2 = Vector2::new(0.0, -980.0);
+const DAMPING: f32 = 1.0;
+const FREEZING_THRESHOLD: f32 = 1e-4;
+// slower wall contacts are a ball resting against the wall, not an impact
+const IMPACT_SPEED: f32 = 30.0;
+// magnet pairs further apart than this many times their touching distance are ignored, the force having fallen off with 1/r^4
+const MAGNET_RANGE: f32 = 3.0;
+
+// everything a world needs is kept in it, so any number of them can be stepped side by side;
+// fields missing from older saves take their defaults
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(remote = "Self", default)]
+pub struct World {
+    pub balls: Vec<Ball>,
+    // names for balls, by which joint networks are put onto them
+    pub tags: BTreeMap<String, usize>,
+    #[serde(with = "Vector2Def")]
+    pub gravity: Vector2,
+    // the walls, in world coordinates with y up
+    #[serde(with = "RectangleDef")]
+    pub bounds: Rectangle,
+    // solver passes per step; more make stacks and chains stiffer
+    pub collision_iterations: usize,
+    pub joint_iterations: usize,
+    // scales the restitution of the walls
+    pub damping: f32,
+    // balls slower than this are counted towards falling asleep
+    pub freezing_threshold: f32,
+    // wall hits faster than this count as bounces
+    pub impact_speed: f32,
+    pub magnet_range: f32,
+    pub joints: Vec<Joint>,
+    pub couplings: Vec<Coupling>,
+    #[serde(skip)]
+    pub events: Vec<Event>,
+    pub fields: Vec<ForceField>,
+    pub time_zones: Vec<TimeZone>,
+    pub thrusters: Vec<Thruster>,
+    pub thermostat: Thermostat,
+    pub drag: f32,
+    // wrapped sides are periodic: balls leaving through one come back through the other, and collide across the seam
+    pub wrap_x: bool,
+    pub wrap_y: bool,
+    pub terrain: Option<Heightfield>,
+    // applies to every ball without a limit of its own
+    pub bounce_limit: Option<BounceLimit>,
+    // ball pairs that touched during the last step
+    #[serde(skip)]
+    pub contacts: usize,
+    // of those, the pairs that were not touching the step before
+    #[serde(skip)]
+    pub new_contacts: usize,
+    // static circles moved from outside the simulation; their velocity is what they hit balls with
+    #[serde(skip)]
+    pub kinematic: Vec<Ball>,
+    // collisions above this impulse are reported as events
+    #[serde(skip)]
+    pub impact_threshold: Option<f32>,
+
+    #[serde(skip)]
+    grid: Grid,
+    #[serde(skip)]
+    magnet_grid: Grid,
+    // the ids of the pairs that touched during the last step, sleeping pairs staying in while they sleep
+    #[serde(skip)]
+    touching: HashSet<(usize, usize)>,
+    next_ball_id: usize,
+    next_joint_id: usize,
+    next_coupling_id: usize,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self {
+            balls: Vec::new(),
+            tags: BTreeMap::new(),
+            gravity: GRAVITY,
+            bounds: BOUNDS,
+            collision_iterations: COLLISION_ITERATIONS,
+            joint_iterations: JOINT_ITERATIONS,
+            damping: DAMPING,
+            freezing_threshold: FREEZING_THRESHOLD,
+            impact_speed: IMPACT_SPEED,
+            magnet_range: MAGNET_RANGE,
+            joints: Vec::new(),
+            couplings: Vec::new(),
+            events: Vec::new(),
+            fields: Vec::new(),
+            time_zones: Vec::new(),
+            thrusters: Vec::new(),
+            thermostat: Thermostat::default(),
+            drag: 0.0,
+            wrap_x: false,
+            wrap_y: false,
+            terrain: None,
+            bounce_limit: None,
+            contacts: 0,
+            new_contacts: 0,
+            kinematic: Vec::new(),
+            impact_threshold: None,
+            grid: Grid::default(),
+            magnet_grid: Grid::default(),
+            touching: HashSet::new(),
+            next_ball_id: 0,
+            next_joint_id: 0,
+            next_coupling_id: 0,
+        }
+    }
+}
+
+// the id counters are saved too, but a save without them, or one edited by hand, must still not hand out
+// ids the world already uses
+impl<'de> Deserialize<'de> for World {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut world = World::deserialize(deserializer)?;
+        world.next_ball_id = world.next_ball_id.max(world.balls.iter().map(|b| b.id + 1).max().unwrap_or(0));
+        world.next_joint_id = world.next_joint_id.max(world.joints.iter().map(|j| j.id + 1).max().unwrap_or(0));
+        world.next_coupling_id = world.next_coupling_id.max(world.couplings.iter().map(|c| c.id + 1).max().unwrap_or(0));
+        Ok(world)
+    }
+}
+
+impl Serialize for World {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        World::serialize(self, serializer)
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // a copy to try things on without touching this world; pending events stay behind so nothing is reported twice
+    pub fn fork(&self) -> World {
+        let mut fork = self.clone();
+        fork.events.clear();
+        fork
+    }
+
+    // where the given balls would go over the next `steps` steps, one path per id starting at where they are now;
+    // the path of a ball that gets removed on the way stops there. this steps the world itself, so call it on a fork
+    pub fn predict(self, ids: &[usize], steps: usize, dt: f32) -> Vec<Vec<Vector2>> {
+        let mut fork = self;
+        let mut paths: Vec<Vec<Vector2>> = ids.iter().map(|&id| fork.ball(id).map(|b| b.center).into_iter().collect()).collect();
+        for _ in 0..steps {
+            fork.step(dt);
+            fork.events.clear();
+            for (path, &id) in paths.iter_mut().zip(ids) {
+                path.extend(fork.ball(id).map(|b| b.center));
+            }
+        }
+        paths
+    }
+
+    pub fn spawn(&mut self, center: Vector2, radius: f32, color: Color) -> usize {
+        self.add_ball(Ball::new(center, radius, color))
+    }
+
+    // ids stay valid until the thing is removed and are never reused
+    pub fn add_ball(&mut self, mut ball: Ball) -> usize {
+        ball.id = self.next_ball_id;
+        self.next_ball_id += 1;
+
+        self.balls.push(ball);
+        ball.id
+    }
+
+    pub fn ball(&self, id: usize) -> Option<&Ball> {
+        self.balls.iter().find(|b| b.id == id)
+    }
+
+    pub fn ball_mut(&mut self, id: usize) -> Option<&mut Ball> {
+        self.balls.iter_mut().find(|b| b.id == id)
+    }
+
+    // sets a ball's radius and mass, growing it no further than its neighbours and the walls allow, though a ball
+    // already overlapping something keeps its size; returns the radius it got
+    pub fn resize_ball(&mut self, id: usize, radius: f32) -> Option<f32> {
+        let ball = *self.ball(id)?;
+        let period = self.period();
+        let bounds = self.bounds;
+
+        let mut room = f32::INFINITY;
+        if !self.wrap_x {
+            room = room.min(ball.center.x - bounds.x).min(bounds.x + bounds.width - ball.center.x);
+        }
+        if !self.wrap_y {
+            room = room.min(ball.center.y - bounds.y).min(bounds.y + bounds.height - ball.center.y);
+        }
+        for other in self.balls.iter().filter(|b| b.id != id) {
+            room = room.min(minimum_image(other.center - ball.center, period).length() - other.radius);
+        }
+
+        let radius = radius.min(room.max(ball.radius)).max(MIN_RADIUS);
+        self.ball_mut(id)?.set_radius(radius);
+        Some(radius)
+    }
+
+    // removes the balls along with every joint attached to them
+    pub fn remove_balls(&mut self, ids: &[usize]) {
+        self.balls.retain(|b| !ids.contains(&b.id));
+        self.thrusters.retain(|t| !ids.contains(&t.ball));
+        self.tags.retain(|_, id| !ids.contains(id));
+
+        let removed: Vec<usize> = self.joints.iter()
+            .filter(|j| ids.contains(&j.a) || ids.contains(&j.b))
+            .map(|j| j.id)
+            .collect();
+        for id in removed {
+            self.remove_joint(id);
+        }
+    }
+
+    // a ball has at most one tag, and a tag names at most one ball: retagging a ball moves its tag, but a tag
+    // already naming another ball is refused, returning false
+    pub fn tag_ball(&mut self, id: usize, tag: &str) -> bool {
+        if self.tagged(tag).is_some_and(|other| other != id) {
+            return false;
+        }
+        self.tags.retain(|_, tagged| *tagged != id);
+        self.tags.insert(tag.to_string(), id);
+        true
+    }
+
+    pub fn tagged(&self, tag: &str) -> Option<usize> {
+        self.tags.get(tag).copied().filter(|&id| self.ball(id).is_some())
+    }
+
+    pub fn tag_of(&self, id: usize) -> Option<&str> {
+        self.tags.iter().find(|(_, &tagged)| tagged == id).map(|(tag, _)| tag.as_str())
+    }
+
+    pub fn add_joint(&mut self, mut joint: Joint) -> usize {
+        joint.id = self.next_joint_id;
+        self.next_joint_id += 1;
+
+        self.joints.push(joint);
+        joint.id
+    }
+
+    pub fn joint(&self, id: usize) -> Option<&Joint> {
+        self.joints.iter().find(|j| j.id == id)
+    }
+
+    pub fn remove_joint(&mut self, id: usize) {
+        self.joints.retain(|j| j.id != id);
+        self.couplings.retain(|c| !c.couples(id));
+
+        // hinges measured against the removed joint lose their frame of reference
+        for joint in &mut self.joints {
+            if joint.parent == Some(id) {
+                joint.parent = None;
+                joint.limit = None;
+                joint.motor = None;
+            }
+        }
+    }
+
+    pub fn add_coupling(&mut self, mut coupling: Coupling) -> usize {
+        coupling.id = self.next_coupling_id;
+        self.next_coupling_id += 1;
+
+        self.couplings.push(coupling);
+        coupling.id
+    }
+
+    pub fn joint_at(&self, point: Vector2, tolerance: f32) -> Option<usize> {
+        self.joints.iter()
+            .filter_map(|j| {
+                let distance = j.distance_to(self.ball(j.a)?, self.ball(j.b)?, point);
+                (distance <= tolerance).then_some((j.id, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    pub fn step(&mut self, dt: f32) {
+        // each ball lives through its own share of the step; collisions only fix up positions and velocities,
+        // so they run on the whole step for everyone, while springs and motors act over time and run on
+        // the share of the slower end
+        let scales = self.time_scales();
+
+        self.animate(dt, &scales);
+        self.apply_forces(dt, &scales);
+        self.apply_magnets(dt, &scales);
+        if self.thermostat.is_on() {
+            for (ball, scale) in self.balls.iter_mut().zip(&scales).filter(|(b, _)| !b.is_static) {
+                self.thermostat.apply(ball, dt * scale);
+            }
+        }
+
+        for (ball, scale) in self.balls.iter_mut().zip(&scales) {
+            ball.integrate(self.gravity, dt * scale);
+        }
+
+        self.collide();
+
+        self.settle();
+
+        let mut impulses = vec![0.0; self.joints.len()];
+
+        let substep = dt / self.joint_iterations.max(1) as f32;
+        let scale_of = |id: usize| self.balls.iter().position(|b| b.id == id).map_or(1.0, |i| scales[i]);
+        let joint_scales: Vec<f32> = self.joints.iter().map(|j| scale_of(j.a).min(scale_of(j.b))).collect();
+        let period = self.period();
+        for _ in 0..self.joint_iterations {
+            for ((joint, impulse), scale) in self.joints.iter().zip(impulses.iter_mut()).zip(&joint_scales) {
+                if let Some((a, b)) = pair_mut(&mut self.balls, joint.a, joint.b) {
+                    // a joint spanning a periodic seam pulls the short way round
+                    let delta = b.center - a.center;
+                    let offset = minimum_image(delta, period) - delta;
+                    b.center += offset;
+                    *impulse += joint.solve(a, b, substep * scale);
+                    b.center -= offset;
+                }
+            }
+
+            for (i, scale) in joint_scales.iter().enumerate() {
+                self.solve_hinge(self.joints[i], substep * scale);
+            }
+
+            for i in 0..self.couplings.len() {
+                self.solve_coupling(self.couplings[i]);
+            }
+        }
+
+        self.break_joints(&impulses);
+    }
+
+    // sets the friction of every moving ball and of the ground
+    pub fn set_friction(&mut self, friction: f32) {
+        for ball in self.balls.iter_mut().filter(|b| !b.is_static) {
+            ball.material.friction = friction;
+        }
+        if let Some(terrain) = &mut self.terrain {
+            terrain.friction = friction;
+        }
+    }
+
+    // kinetic plus potential energy of the moving balls
+    pub fn energy(&self) -> f32 {
+        self.balls.iter()
+            .filter(|b| !b.is_static)
+            .map(|b| 0.5 * b.mass * b.velocity.length_sqr() - b.mass * self.gravity.dot(b.center))
+            .sum()
+    }
+
+    // the size of each periodic side, zero along sides with walls
+    pub fn period(&self) -> Vector2 {
+        Vector2::new(
+            if self.wrap_x { self.bounds.width } else { 0.0 },
+            if self.wrap_y { self.bounds.height } else { 0.0 },
+        )
+    }
+
+    // copies of the balls close enough to a periodic seam to touch a ball on the other side,
+    // shifted by a period, as (ball index, offset)
+    fn ghosts(&self) -> Vec<(usize, Vector2)> {
+        if !self.wrap_x && !self.wrap_y {
+            return Vec::new();
+        }
+
+        let reach = 2.0 * self.balls.iter().map(|b| b.radius).fold(0.0, f32::max);
+        let shifts = |x: f32, period: f32, periodic: bool| {
+            let mut shifts = vec![0.0];
+            if periodic && x < reach {
+                shifts.push(period);
+            }
+            if periodic && x > period - reach {
+                shifts.push(-period);
+            }
+            shifts
+        };
+
+        let mut ghosts = Vec::new();
+        for (i, ball) in self.balls.iter().enumerate() {
+            for &dx in &shifts(ball.center.x - self.bounds.x, self.bounds.width, self.wrap_x) {
+                for &dy in &shifts(ball.center.y - self.bounds.y, self.bounds.height, self.wrap_y) {
+                    if dx != 0.0 || dy != 0.0 {
+                        ghosts.push((i, Vector2::new(dx, dy)));
+                    }
+                }
+            }
+        }
+        ghosts
+    }
+
+    // ball index pairs to collide, the second one shifted by the offset; across a periodic seam
+    // a pair is found through the ghost of its higher index ball
+    fn collision_pairs(&mut self) -> Vec<(usize, usize, Vector2)> {
+        let ghosts = self.ghosts();
+        if ghosts.is_empty() {
+            return self.grid.pairs(&self.balls).iter().map(|&(i, j)| (i, j, Vector2::zero())).collect();
+        }
+
+        let count = self.balls.len();
+        let mut bodies = self.balls.clone();
+        bodies.extend(ghosts.iter().map(|&(i, offset)| {
+            let mut ghost = self.balls[i];
+            ghost.center += offset;
+            ghost
+        }));
+
+        self.grid.pairs(&bodies).iter()
+            .filter_map(|&(i, j)| match (i < count, j < count) {
+                (true, true) => Some((i, j, Vector2::zero())),
+                (true, false) if i < ghosts[j - count].0 => Some((i, ghosts[j - count].0, ghosts[j - count].1)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // how fast time runs for each ball, from the time zones around its center
+    pub fn time_scales(&self) -> Vec<f32> {
+        self.balls.iter().map(|b| time_zone::scale_at(&self.time_zones, b.center)).collect()
+    }
+
+    // steps the balls' pulses, keeping them above the smallest radius; a finished ramp is dropped
+    fn animate(&mut self, dt: f32, scales: &[f32]) {
+        for (ball, scale) in self.balls.iter_mut().zip(scales) {
+            let dt = dt * scale;
+            ball.growth = 0.0;
+            let Some(mut pulse) = ball.pulse else {
+                continue;
+            };
+            if dt <= 0.0 {
+                continue;
+            }
+
+            let radius = pulse.advance(ball.radius, dt).max(MIN_RADIUS);
+            ball.growth = (radius - ball.radius) / dt;
+            ball.set_radius(radius);
+            ball.pulse = if pulse.is_done(radius) { None } else { Some(pulse) };
+        }
+    }
+
+    fn collide(&mut self) {
+        let pairs = self.collision_pairs();
+        let mut touching = HashSet::new();
+        self.contacts = 0;
+        self.new_contacts = 0;
+
+        // resting piles need a few passes for the contacts to agree with each other
+        for iteration in 0..self.collision_iterations {
+            for &(i, j, offset) in &pairs {
+                let (a, b) = two_mut(&mut self.balls, i, j);
+                let key = (a.id.min(b.id), a.id.max(b.id));
+                if !a.is_awake() && !b.is_awake() && a.growth == 0.0 && b.growth == 0.0 {
+                    if iteration == 0 && self.touching.contains(&key) {
+                        touching.insert(key);
+                    }
+                    continue;
+                }
+                b.center += offset;
+                let collision = a.collide(b);
+                b.center -= offset;
+                let Some(impulse) = collision else {
+                    continue;
+                };
+                a.wake_if_moving(self.freezing_threshold);
+                b.wake_if_moving(self.freezing_threshold);
+
+                if iteration == 0 {
+                    self.contacts += 1;
+                    if !self.touching.contains(&key) {
+                        self.new_contacts += 1;
+                    }
+                    touching.insert(key);
+                    if self.impact_threshold.is_some_and(|threshold| impulse > threshold) {
+                        let position = (a.center * b.radius + b.center * a.radius) / (a.radius + b.radius);
+                        self.events.push(Event::Impact { a: a.id, b: b.id, position, impulse });
+                    }
+                }
+            }
+
+            if let Some(terrain) = &self.terrain {
+                for ball in self.balls.iter_mut().filter(|b| b.is_awake()) {
+                    terrain.collide(ball);
+                }
+            }
+
+            for body in &mut self.kinematic {
+                for ball in self.balls.iter_mut().filter(|b| !b.is_static) {
+                    if ball.collide(body).is_some() {
+                        ball.wake_if_moving(self.freezing_threshold);
+                    }
+                }
+            }
+        }
+        self.touching = touching;
+    }
+
+    fn settle(&mut self) {
+        let mut despawned = Vec::new();
+
+        for ball in &mut self.balls {
+            if ball.settle(self.bounds, self.wrap_x, self.wrap_y, self.damping, self.freezing_threshold) <= self.impact_speed {
+                continue;
+            }
+            ball.bounces += 1;
+
+            let Some(limit) = ball.bounce_limit.or(self.bounce_limit) else {
+                continue;
+            };
+            if ball.bounces != limit.count {
+                continue;
+            }
+
+            match limit.rule {
+                BounceRule::Inelastic => ball.material.restitution = 0.0,
+                BounceRule::Despawn => despawned.push(ball.id),
+            }
+            self.events.push(Event::BouncesExhausted { ball: ball.id, position: ball.center, rule: limit.rule });
+        }
+
+        if !despawned.is_empty() {
+            self.remove_balls(&despawned);
+        }
+    }
+
+    fn apply_forces(&mut self, dt: f32, scales: &[f32]) {
+        for thruster in self.thrusters.iter().filter(|t| t.throttle > 0.0) {
+            if let Some(i) = self.balls.iter().position(|b| b.id == thruster.ball && !b.is_static) {
+                let ball = &mut self.balls[i];
+                ball.velocity += thruster.thrust() / ball.mass * dt * scales[i];
+                ball.freezing = ball.freezing.max(10);
+            }
+        }
+
+        if self.fields.is_empty() && self.drag == 0.0 {
+            return;
+        }
+
+        for (ball, scale) in self.balls.iter_mut().zip(scales).filter(|(b, _)| !b.is_static) {
+            let mut acceleration = ball.drag_force(self.drag) / ball.mass;
+            for field in self.fields.iter().filter(|f| f.contains(ball.center)) {
+                acceleration += field.acceleration;
+                ball.freezing = ball.freezing.max(10);
+            }
+
+            ball.velocity += acceleration * (dt * scale);
+        }
+    }
+
+    // only magnetized balls go through their own broad-phase, with radii stretched to the magnets' range
+    fn apply_magnets(&mut self, dt: f32, scales: &[f32]) {
+        let indices: Vec<usize> = (0..self.balls.len()).filter(|&i| self.balls[i].magnet.is_some()).collect();
+        if indices.len() < 2 {
+            return;
+        }
+
+        let magnets: Vec<Ball> = indices.iter().map(|&i| self.balls[i]).collect();
+        for &(i, j) in self.magnet_grid.pairs_within(&magnets, self.magnet_range) {
+            let (dt_a, dt_b) = (dt * scales[indices[i]], dt * scales[indices[j]]);
+            let (a, b) = two_mut(&mut self.balls, indices[i], indices[j]);
+            if !a.is_awake() && !b.is_awake() {
+                continue;
+            }
+            let (Some(mut ma), Some(mut mb)) = (a.magnet, b.magnet) else {
+                continue;
+            };
+            let Some((force, torque_a, torque_b)) = magnet::interact(a, ma.vector(), b, mb.vector(), self.magnet_range) else {
+                continue;
+            };
+
+            a.velocity -= force * (a.inverse_mass() * dt_a);
+            b.velocity += force * (b.inverse_mass() * dt_b);
+            // a solid disc's moment of inertia
+            ma.turn(torque_a, 0.5 * a.mass * a.radius * a.radius, dt_a);
+            mb.turn(torque_b, 0.5 * b.mass * b.radius * b.radius, dt_b);
+            a.magnet = Some(ma);
+            b.magnet = Some(mb);
+
+            a.wake_if_moving(self.freezing_threshold);
+            b.wake_if_moving(self.freezing_threshold);
+        }
+    }
+
+    fn break_joints(&mut self, impulses: &[f32]) {
+        let broken: Vec<(Joint, f32)> = self.joints.iter()
+            .zip(impulses)
+            .filter(|(j, &impulse)| j.break_impulse.is_some_and(|limit| impulse > limit))
+            .map(|(j, &impulse)| (*j, impulse))
+            .collect();
+
+        for (joint, impulse) in broken {
+            let position = match (self.ball(joint.a), self.ball(joint.b)) {
+                (Some(a), Some(b)) => (a.center + b.center) / 2.0,
+                _ => continue,
+            };
+
+            self.remove_joint(joint.id);
+            self.events.push(Event::JointBroken { joint: joint.id, a: joint.a, b: joint.b, position, impulse });
+        }
+    }
+
+    fn solve_hinge(&mut self, joint: Joint, dt: f32) {
+        if !joint.is_hinge() {
+            return;
+        }
+
+        self.with_hinge(joint, |hinge| {
+            if let Some(motor) = joint.motor {
+                motor.solve(hinge, dt);
+            }
+            if let Some(limit) = joint.limit {
+                limit.solve(hinge);
+            }
+        });
+    }
+
+    fn solve_coupling(&mut self, coupling: Coupling) {
+        let (first, second) = match (self.joint(coupling.first), self.joint(coupling.second)) {
+            (Some(first), Some(second)) => (*first, *second),
+            _ => return,
+        };
+        let ratio = coupling.ratio;
+
+        match coupling.kind {
+            CouplingKind::Gear => {
+                let state = |world: &mut World, joint| world.with_hinge(joint, |h| (h.spin(), h.inverse_inertia()));
+                let ((spin_first, w_first), (spin_second, w_second)) = match (state(self, first), state(self, second)) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return,
+                };
+
+                let w_total = w_first + ratio * ratio * w_second;
+                if w_total == 0.0 {
+                    return;
+                }
+
+                let lambda = -(spin_first + ratio * spin_second) / w_total;
+                self.with_hinge(first, |h| h.add_spin(lambda * w_first));
+                self.with_hinge(second, |h| h.add_spin(lambda * ratio * w_second));
+            }
+            CouplingKind::Pulley { total } => {
+                let state = |world: &World, joint: Joint| {
+                    let (a, b) = (world.ball(joint.a)?, world.ball(joint.b)?);
+                    let normal = (b.center - a.center).normalized();
+                    Some((a.center.distance_to(b.center), (b.velocity - a.velocity).dot(normal), a.inverse_mass() + b.inverse_mass()))
+                };
+                let ((length_first, closing_first, inv_first), (length_second, closing_second, inv_second)) = match (state(self, first), state(self, second)) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return,
+                };
+
+                let inv_total = inv_first + ratio * ratio * inv_second;
+                let stretch = length_first + ratio * length_second - total;
+                if inv_total == 0.0 || stretch <= 0.0 {
+                    return;
+                }
+
+                // like a rope, the pulley only resists lengthening
+                let position = -stretch / inv_total;
+                let impulse = -(closing_first + ratio * closing_second).max(0.0) / inv_total;
+
+                for (joint, scale) in [(first, 1.0), (second, ratio)] {
+                    if let Some((a, b)) = pair_mut(&mut self.balls, joint.a, joint.b) {
+                        joint.push(a, b, position * scale, impulse * scale);
+                    }
+                }
+            }
+        }
+    }
+
+    // runs `f` on the hinge around the joint's `a` ball, writing the moved balls back
+    fn with_hinge<R>(&mut self, joint: Joint, f: impl FnOnce(&mut Hinge) -> R) -> Option<R> {
+        let index = |id: usize| self.balls.iter().position(|b| b.id == id);
+        let pivot = index(joint.a)?;
+        let child_end = index(joint.b)?;
+        let parent_end = match joint.parent {
+            Some(parent) => match self.joint(parent) {
+                Some(parent) if parent.a == joint.a || parent.b == joint.a => Some(index(parent.other(joint.a))?),
+                _ => return None,
+            },
+            None => None,
+        };
+
+        let pivot_ball = self.balls[pivot];
+        let mut child_ball = self.balls[child_end];
+        let mut parent_ball = parent_end.map(|i| self.balls[i]);
+
+        let result = f(&mut Hinge {
+            parent_end: parent_ball.as_mut(),
+            pivot: &pivot_ball,
+            child_end: &mut child_ball,
+            reference: joint.reference,
+        });
+
+        self.balls[child_end] = child_ball;
+        if let (Some(i), Some(ball)) = (parent_end, parent_ball) {
+            self.balls[i] = ball;
+        }
+
+        Some(result)
+    }
+
+    pub fn draw(&self, cam: &Camera, accessible: bool, d: &mut impl Renderer) {
+        for zone in &self.time_zones {
+            zone.draw(cam, d);
+        }
+
+        if let Some(terrain) = &self.terrain {
+            terrain.draw(cam, d);
+        }
+
+        for ball in &self.balls {
+            ball.draw(cam, accessible, d);
+        }
+
+        for joint in &self.joints {
+            if let (Some(a), Some(b)) = (self.ball(joint.a), self.ball(joint.b)) {
+                joint.draw(a, b, cam, None, d);
+            }
+        }
+    }
+}
+
+pub(crate) fn pair_mut(balls: &mut [Ball], a: usize, b: usize) -> Option<(&mut Ball, &mut Ball)> {
+    let i = balls.iter().position(|ball| ball.id == a)?;
+    let j = balls.iter().position(|ball| ball.id == b)?;
+
+    if i == j {
+        return None;
+    }
+
+    Some(two_mut(balls, i, j))
+}
+
+fn two_mut(balls: &mut [Ball], i: usize, j: usize) -> (&mut Ball, &mut Ball) {
+    if i < j {
+        let (left, right) = balls.split_at_mut(j);
+        (&mut left[i], &mut right[0])
+    } else {
+        let (left, right) = balls.split_at_mut(i);
+        (&mut right[0], &mut left[j])
+    }
+}
+
+// the shortest separation between two points, through a periodic seam if that is closer; a zero period is not periodic
+pub fn minimum_image(delta: Vector2, period: Vector2) -> Vector2 {
+    let wrap = |d: f32, period: f32| if period > 0.0 { d - period * (d / period).round() } else { d };
+    Vector2::new(wrap(delta.x, period.x), wrap(delta.y, period.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joint::JointKind;
+
+    #[test]
+    fn minimum_image_takes_the_shorter_way_round() {
+        let period = Vector2::new(640.0, 480.0);
+        assert_eq!(minimum_image(Vector2::new(630.0, 10.0), period), Vector2::new(-10.0, 10.0));
+        assert_eq!(minimum_image(Vector2::new(-300.0, -470.0), period), Vector2::new(-300.0, 10.0));
+        assert_eq!(minimum_image(Vector2::new(630.0, 470.0), Vector2::zero()), Vector2::new(630.0, 470.0));
+    }
+
+    fn wrapped(centers: &[(f32, f32)]) -> World {
+        let mut world = World::new();
+        world.wrap_x = true;
+        world.wrap_y = true;
+        for &(x, y) in centers {
+            world.add_ball(Ball::new(Vector2::new(x, y), 10.0, Color::RED));
+        }
+        world
+    }
+
+    #[test]
+    fn seam_pairs_are_found_once() {
+        let mut world = wrapped(&[(5.0, 240.0), (635.0, 240.0)]);
+        let pairs = world.collision_pairs();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].0, pairs[0].1), (0, 1));
+        assert_eq!(pairs[0].2, Vector2::new(-640.0, 0.0));
+    }
+
+    #[test]
+    fn corner_pairs_are_found_once() {
+        let mut world = wrapped(&[(5.0, 5.0), (635.0, 475.0), (635.0, 5.0), (5.0, 475.0)]);
+        let mut pairs: Vec<(usize, usize)> = world.collision_pairs().iter().map(|&(i, j, _)| (i, j)).collect();
+        pairs.sort();
+        assert_eq!(pairs, [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn pairs_beside_a_seam_are_not_doubled_by_their_ghosts() {
+        let mut world = wrapped(&[(5.0, 240.0), (15.0, 240.0)]);
+        let pairs = world.collision_pairs();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].2, Vector2::zero());
+    }
+
+    #[test]
+    fn retagging_refuses_a_tag_another_ball_has() {
+        let mut world = World::new();
+        let pivot = world.spawn(Vector2::new(400.0, 100.0), 10.0, Color::RED);
+        let bob = world.spawn(Vector2::new(400.0, 200.0), 10.0, Color::RED);
+        world.tag_ball(pivot, "pivot");
+        assert!(!world.tag_ball(bob, "pivot"));
+        assert_eq!(world.tagged("pivot"), Some(pivot));
+        assert!(world.tag_ball(pivot, "hub"));
+        assert_eq!(world.tagged("pivot"), None);
+    }
+
+    #[test]
+    fn a_resting_contact_starts_once() {
+        let mut world = World::new();
+        let floor = world.add_ball(Ball::new(Vector2::new(320.0, 100.0), 20.0, Color::RED));
+        world.ball_mut(floor).unwrap().set_static(true);
+        world.add_ball(Ball::new(Vector2::new(320.0, 130.0), 10.0, Color::RED));
+
+        let (mut contacts, mut onsets) = (0, 0);
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            contacts += world.contacts;
+            onsets += world.new_contacts;
+        }
+        assert!(contacts > 10);
+        assert_eq!(onsets, 1);
+    }
+
+    #[test]
+    fn a_save_without_id_counters_hands_out_fresh_ids() {
+        let mut world = World::new();
+        let a = world.add_ball(Ball::new(Vector2::new(100.0, 100.0), 10.0, Color::RED));
+        let b = world.add_ball(Ball::new(Vector2::new(200.0, 100.0), 10.0, Color::RED));
+        world.add_joint(Joint::new(a, b, 100.0, JointKind::Distance));
+        let mut json = serde_json::to_value(&world).unwrap();
+        for counter in ["next_ball_id", "next_joint_id", "next_coupling_id"] {
+            json.as_object_mut().unwrap().remove(counter);
+        }
+
+        let mut loaded: World = serde_json::from_value(json).unwrap();
+        let c = loaded.add_ball(Ball::new(Vector2::new(300.0, 100.0), 10.0, Color::RED));
+        assert!(c != a && c != b);
+        let joint = loaded.add_joint(Joint::new(b, c, 100.0, JointKind::Distance));
+        assert_ne!(joint, world.joints[0].id);
+    }
+
+    #[test]
+    fn a_spring_pendulum_in_a_slow_zone_swings_at_the_zone_pace() {
+        let pendulum = |scale: f32| {
+            let mut world = World::new();
+            if scale < 1.0 {
+                world.time_zones.push(TimeZone::new(Rectangle::new(0.0, 0.0, 640.0, 480.0), scale));
+            }
+            let pivot = world.add_ball(Ball::new(Vector2::new(320.0, 400.0), 5.0, Color::RED));
+            world.ball_mut(pivot).unwrap().set_static(true);
+            let bob = world.add_ball(Ball::new(Vector2::new(400.0, 350.0), 10.0, Color::RED));
+            world.add_joint(Joint::new(pivot, bob, 100.0, JointKind::Spring { stiffness: 400.0, damping: 0.0 }));
+            (world, bob)
+        };
+
+        // half a step in the zone is a half step outside it
+        let (mut slow, bob) = pendulum(0.5);
+        let (mut free, _) = pendulum(1.0);
+        for _ in 0..120 {
+            slow.step(1.0 / 60.0);
+            free.step(0.5 / 60.0);
+        }
+        let (a, b) = (slow.ball(bob).unwrap().center, free.ball(bob).unwrap().center);
+        assert!((a - b).length() < 0.5, "{:?} vs {:?}", a, b);
+    }
+}